@@ -0,0 +1,83 @@
+// benches/syncbump.rs
+
+//! 多線程分配小字符串時，共享的 CAS 快速路徑與線程私有的 `LocalBump` 的對比。
+//!
+//! 運行：`cargo bench --bench syncbump`
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use interb::SyncBump;
+use std::hint::black_box;
+use std::thread;
+
+/// 每個線程分配的字符串數量。
+const ALLOCS_PER_THREAD: usize = 10_000;
+
+/// 參與測試的線程數：1、4，以及本機的全部核心。
+fn thread_counts() -> Vec<usize> {
+    let cores = thread::available_parallelism().map_or(4, |n| n.get());
+    let mut counts = vec![1, 4, cores];
+    counts.sort_unstable();
+    counts.dedup();
+    counts
+}
+
+/// 每個線程要分配的字符串，在計時之外準備好。
+fn words() -> Vec<String> {
+    (0..ALLOCS_PER_THREAD)
+        .map(|i| format!("ident_{i}"))
+        .collect()
+}
+
+fn bench_small_strings(c: &mut Criterion) {
+    let words = words();
+    let mut group = c.benchmark_group("syncbump_small_strings");
+
+    for threads in thread_counts() {
+        group.throughput(Throughput::Elements((threads * ALLOCS_PER_THREAD) as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("shared_cas", threads),
+            &threads,
+            |b, &n| {
+                b.iter(|| {
+                    let arena = SyncBump::new();
+                    thread::scope(|s| {
+                        for _ in 0..n {
+                            s.spawn(|| {
+                                for word in &words {
+                                    black_box(arena.alloc_str(word));
+                                }
+                            });
+                        }
+                    });
+                    arena
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("local_block", threads),
+            &threads,
+            |b, &n| {
+                b.iter(|| {
+                    let arena = SyncBump::new();
+                    thread::scope(|s| {
+                        for _ in 0..n {
+                            s.spawn(|| {
+                                let local = arena.local();
+                                for word in &words {
+                                    black_box(local.alloc_str(word));
+                                }
+                            });
+                        }
+                    });
+                    arena
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_small_strings);
+criterion_main!(benches);
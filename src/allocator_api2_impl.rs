@@ -0,0 +1,204 @@
+// src/allocator_api2_impl.rs
+
+//! `allocator-api2` 特性：讓 [`SyncBump`] 成為 `allocator_api2` 的分配器。
+//!
+//! `&SyncBump` 實現了 [`Allocator`]，所以 `allocator_api2` 的 `Vec`、`Box`，
+//! 以及 `hashbrown` 的 `HashMap` 等集合都可以把內存分配在一個共享的 SyncBump 中：
+//!
+//! ```
+//! use allocator_api2::vec::Vec;
+//! use interb::SyncBump;
+//!
+//! let arena = SyncBump::new();
+//! let mut v = Vec::new_in(&arena);
+//! v.extend([1, 2, 3]);
+//! assert_eq!(v, [1, 2, 3]);
+//! ```
+//!
+//! 釋放、收縮和擴展只有對最近的一次分配（位於當前 chunk 的 `top` 處）才會真正回收或重用空間：
+//! 不斷 `push` 的 `Vec` 在沒有其他分配插進來時可以原地增長，而不會在 Arena 中留下一串舊的緩衝區。
+//! 其他情況下，釋放什麼都不做，內存在 SyncBump 被析構時統一歸還。
+
+use crate::syncbump::SyncBump;
+use allocator_api2::alloc::{AllocError, Allocator};
+use std::alloc::Layout;
+use std::ptr::NonNull;
+
+unsafe impl<const MIN_ALIGN: usize> Allocator for &SyncBump<MIN_ALIGN> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.try_alloc_layout(layout)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .map_err(|_| AllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { SyncBump::dealloc(self, ptr, layout) }
+    }
+
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { SyncBump::shrink(self, ptr, old_layout, new_layout) }
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+            .map_err(|_| AllocError)
+    }
+
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { SyncBump::grow(self, ptr, old_layout, new_layout) }
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+            .map_err(|_| AllocError)
+    }
+
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let new_ptr = unsafe { self.grow(ptr, old_layout, new_layout)? };
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .as_ptr()
+                .add(old_layout.size())
+                .write_bytes(0, new_layout.size() - old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use allocator_api2::boxed::Box;
+    use allocator_api2::vec::Vec;
+
+    #[test]
+    fn test_vec_and_box_in_arena() {
+        let arena = SyncBump::new();
+        let mut v = Vec::new_in(&arena);
+        for i in 0..1000u32 {
+            v.push(i);
+        }
+        assert!(v.iter().copied().eq(0..1000));
+
+        let b = Box::new_in([7u64; 4], &arena);
+        assert_eq!(*b, [7; 4]);
+    }
+
+    #[test]
+    fn test_grow_in_place_reuses_last_allocation() {
+        let arena: SyncBump = SyncBump::with_capacity(4096);
+        let mut v: Vec<u64, _> = Vec::with_capacity_in(8, &arena);
+        v.extend(0..8);
+        let old_ptr = v.as_ptr();
+        let allocated = arena.allocated_bytes();
+
+        // 最近的一次分配：新的起始地址緊挨在舊數據的下方，舊的空間被重用
+        v.reserve_exact(8);
+        assert_eq!(v.as_ptr(), old_ptr.wrapping_sub(8));
+        assert!(v.iter().copied().eq(0..8));
+        assert_eq!(arena.allocated_bytes(), allocated);
+    }
+
+    #[test]
+    fn test_grow_after_other_allocation_copies() {
+        let arena = SyncBump::new();
+        let mut v: Vec<u64, _> = Vec::with_capacity_in(4, &arena);
+        v.extend(0..4);
+        let old_ptr = v.as_ptr();
+
+        // 有其他分配插在後面：只能複製到新的位置
+        let other = arena.alloc(42u64);
+        v.extend(4..16);
+        assert_ne!(v.as_ptr(), old_ptr.wrapping_sub(12));
+        assert!(v.iter().copied().eq(0..16));
+        assert_eq!(*other, 42);
+    }
+
+    #[test]
+    fn test_shrink_and_dealloc_last_allocation() {
+        let arena: SyncBump = SyncBump::with_capacity(4096);
+        let mut v: Vec<u64, _> = Vec::with_capacity_in(16, &arena);
+        v.extend(0..4);
+        let old_ptr = v.as_ptr();
+
+        // 收回 12 個元素的空間，數據被挪到舊緩衝區的頂部
+        v.shrink_to_fit();
+        assert_eq!(v.as_ptr(), old_ptr.wrapping_add(12));
+        assert!(v.iter().copied().eq(0..4));
+
+        // 釋放最近的一次分配之後，下一次同樣大小的分配會得到同一塊內存
+        let first = Box::new_in(1u64, &arena);
+        let addr = &*first as *const u64;
+        drop(first);
+        let second = Box::new_in(2u64, &arena);
+        assert_eq!(&*second as *const u64, addr);
+    }
+
+    #[cfg(feature = "padding-stats")]
+    #[test]
+    fn test_padding_stats_follow_dealloc_and_shrink() {
+        let arena = SyncBump::<8>::with_min_align();
+
+        // 3 字节按 MIN_ALIGN 取整为 8：记录 5 字节填充，释放时一并撤销
+        let b = Box::new_in([1u8; 3], &arena);
+        assert_eq!(arena.stats().padding_bytes, Some(5));
+        drop(b);
+        let stats = arena.stats();
+        assert_eq!((stats.used_bytes, stats.padding_bytes), (0, Some(0)));
+
+        // 原地收缩后剩下的尾部计为填充，释放后全部归还
+        let mut v: Vec<u8, _> = Vec::with_capacity_in(64, &arena);
+        v.extend(0..3);
+        v.shrink_to_fit();
+        let stats = arena.stats();
+        assert_eq!(stats.used_bytes - 3, stats.padding_bytes.unwrap());
+        drop(v);
+        assert_eq!(arena.stats().padding_bytes, Some(0));
+    }
+
+    #[test]
+    fn test_hashbrown_map_in_arena() {
+        let arena = SyncBump::new();
+        let mut map = hashbrown::HashMap::with_hasher_in(rustc_hash::FxBuildHasher, &arena);
+        for i in 0..500u32 {
+            map.insert(i, i * 2);
+        }
+        assert_eq!(map.len(), 500);
+        assert!((0..500).all(|i| map[&i] == i * 2));
+    }
+
+    #[test]
+    fn test_concurrent_vecs_share_arena() {
+        use std::thread;
+
+        let arena = SyncBump::new();
+        thread::scope(|s| {
+            for t in 0..4u32 {
+                let arena = &arena;
+                s.spawn(move || {
+                    // 多個線程交替增長各自的 Vec：原地增長與複製兩條路徑都會被走到
+                    let mut v = Vec::new_in(arena);
+                    for i in 0..2000 {
+                        v.push(t * 10_000 + i);
+                    }
+                    assert!(v.iter().copied().eq((0..2000).map(|i| t * 10_000 + i)));
+                });
+            }
+        });
+    }
+}
@@ -15,7 +15,7 @@ pub(super) struct ChunkFooter {
     pub(super) layout: Layout,
 
     /// 指向前一個 Chunk。用於遍歷和釋放。
-    /// 一旦設定後不可變。
+    /// 一旦設定後不可變（除了 `SyncBump::reset` 在獨占訪問時會重寫它）。
     pub(super) prev: NonNull<ChunkFooter>,
 
     /// 原子指針，指向當前可分配內存的頂部（或底部，取決於分配方向）。
@@ -23,7 +23,7 @@ pub(super) struct ChunkFooter {
     pub(super) top: AtomicPtr<u8>,
 
     /// 此 Chunk 及其所有 `prev` Chunks 的總大小。
-    /// 在創建時計算，之後不可變。
+    /// 在創建時計算，之後不可變（除了 `SyncBump::reset` 在獨占訪問時會重寫它）。
     pub(super) allocated_bytes: usize,
 }
 
@@ -68,8 +68,7 @@ impl EmptyChunkFooter {
 impl ChunkFooter {
     // 獲取當前chunk的指針位置（同時也是已分配內存的起始位置）
     // 和已分配內存大小
-    #[cfg(test)]
-    fn get_current_top_and_allocated_size(&self) -> (*const u8, usize) {
+    pub(super) fn get_current_top_and_allocated_size(&self) -> (*const u8, usize) {
         let bottom = self.bottom.as_ptr() as *const u8;
         let top = self.top.load(Ordering::SeqCst) as *const u8;
         debug_assert!(bottom <= top);
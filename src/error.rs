@@ -0,0 +1,108 @@
+// src/error.rs
+
+//! Interner 的錯誤類型。
+
+use crate::syncbump::AllocErr;
+use std::fmt;
+
+/// [`Interner::try_intern`](crate::Interner::try_intern) 失敗的原因。
+///
+/// 長期運行的程序（比如語言服務器）可以根據它優雅地降級，而不是直接 panic。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InternError {
+    /// Symbol 的編號空間已經用完：池中的獨立字符串數量已經達到上限。
+    SymbolOverflow,
+    /// 底層 Arena 向全局分配器申請內存失敗。
+    Alloc(AllocErr),
+    /// 底層 Arena 的分配上限不足以容納這個字符串。
+    AllocationLimitExceeded,
+}
+
+impl fmt::Display for InternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternError::SymbolOverflow => f.write_str("symbol id space exhausted"),
+            InternError::Alloc(err) => write!(f, "interner arena: {err}"),
+            InternError::AllocationLimitExceeded => {
+                f.write_str("interner arena allocation limit exceeded")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InternError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InternError::Alloc(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<AllocErr> for InternError {
+    fn from(err: AllocErr) -> Self {
+        InternError::Alloc(err)
+    }
+}
+
+/// 從快照加載 Interner 失敗的原因，見 [`Interner::from_snapshot`](crate::Interner::from_snapshot)。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SnapshotError {
+    /// 數據開頭不是快照的魔數，這不是一個 interb 快照。
+    BadMagic,
+    /// 快照的格式版本不受支持。
+    UnsupportedVersion(u32),
+    /// 頭部的保留標誌位不為 0：快照可能使用了這個版本還不認識的特性。
+    UnsupportedFlags(u32),
+    /// 數據比頭部聲明的長度短（或長）。
+    LengthMismatch,
+    /// 校驗和不匹配，數據已經損壞。
+    ChecksumMismatch,
+    /// 偏移表不是單調遞增的，或者越過了字符串區的邊界。
+    InvalidOffsets,
+    /// 字符串區不是合法的 UTF-8，或者某個偏移落在了一個字符的中間。
+    InvalidUtf8,
+    /// 快照中出現了重複的字符串，無法保持 Symbol 編號不變。
+    DuplicateString(usize),
+    /// 重建 Interner 時失敗。
+    Intern(InternError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => f.write_str("not an interner snapshot"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {version}")
+            }
+            SnapshotError::UnsupportedFlags(flags) => {
+                write!(f, "unsupported snapshot flags {flags:#x}")
+            }
+            SnapshotError::LengthMismatch => {
+                f.write_str("snapshot length does not match its header")
+            }
+            SnapshotError::ChecksumMismatch => f.write_str("snapshot checksum mismatch"),
+            SnapshotError::InvalidOffsets => f.write_str("snapshot offsets table is invalid"),
+            SnapshotError::InvalidUtf8 => f.write_str("snapshot strings are not valid UTF-8"),
+            SnapshotError::DuplicateString(index) => {
+                write!(f, "snapshot contains a duplicate string at index {index}")
+            }
+            SnapshotError::Intern(err) => write!(f, "failed to rebuild interner: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Intern(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<InternError> for SnapshotError {
+    fn from(err: InternError) -> Self {
+        SnapshotError::Intern(err)
+    }
+}
@@ -0,0 +1,180 @@
+// src/frozen.rs
+
+//! 凍結之後的只讀 Interner。
+//!
+//! 前端結束之後符號表就不再變化了，這時可以用 [`Interner::freeze`] 把 Interner
+//! 轉換為 [`FrozenInterner`]：它不再有任何鎖，所有操作都只是讀取不可變的數據。
+
+use crate::syncbump::SyncBump;
+use crate::{Interner, Symbol, SymbolRepr};
+
+/// 一個按字符串內容排序的 Symbol 編號表，用二分查找代替哈希表來做只讀的查找。
+///
+/// 它不保存字符串本身，字符串由持有者通過 `string` 閉包提供：
+/// 這樣 [`FrozenInterner`] 和 [`MappedInterner`](crate::MappedInterner) 可以共享同一套邏輯。
+pub(crate) struct SortedIndex<S>(Box<[S]>);
+
+impl<S: SymbolRepr> SortedIndex<S> {
+    /// 為編號 `0..len` 的字符串建立排序表。
+    /// 如果有重複的字符串，返回較晚出現的那一個的編號。
+    pub(crate) fn build<'s, F>(len: usize, string: F) -> Result<Self, usize>
+    where
+        F: Fn(usize) -> &'s str,
+    {
+        debug_assert!(len <= S::MAX_COUNT);
+        let mut sorted: Box<[S]> = (0..len).map(S::from_index).collect();
+        sorted.sort_unstable_by_key(|&id| string(id.index()));
+        match sorted
+            .windows(2)
+            .find(|pair| string(pair[0].index()) == string(pair[1].index()))
+        {
+            Some(pair) => Err(pair[0].index().max(pair[1].index())),
+            None => Ok(SortedIndex(sorted)),
+        }
+    }
+
+    /// 二分查找一個字符串的 Symbol。
+    pub(crate) fn find<'s, F>(&self, s: &str, string: F) -> Option<S>
+    where
+        F: Fn(usize) -> &'s str,
+    {
+        self.0
+            .binary_search_by(|&id| string(id.index()).cmp(s))
+            .ok()
+            .map(|position| self.0[position])
+    }
+}
+
+/// 一個凍結的、只讀的字符串駐留池，由 [`Interner::freeze`] 創建。
+///
+/// 它擁有原來 Interner 的 Arena，所有字符串都留在原地；`resolve` 是一次數組訪問，
+/// `get` 是在排序表上的二分查找，兩者都不需要任何鎖，可以在任意多個線程之間自由共享。
+pub struct FrozenInterner<S = Symbol, const MIN_ALIGN: usize = 1> {
+    /// 按 Symbol 編號排列的字符串。
+    /// 與 Interner 一樣，這裡的 `'static` 實際上指向 `arena`，對外總是被縮短到 `&self`。
+    /// 注意：字段按聲明順序析構，它們必須先於 Arena 被釋放。
+    strings: Box<[&'static str]>,
+    /// 用於從字符串查找 Symbol 的排序表。
+    sorted: SortedIndex<S>,
+    /// 原來 Interner 的 Arena，只是為了讓字符串繼續存活。
+    _arena: SyncBump<MIN_ALIGN>,
+}
+
+impl<S: SymbolRepr, const MIN_ALIGN: usize> Interner<S, MIN_ALIGN> {
+    /// 消耗這個 Interner，把它凍結為一個無鎖的只讀 [`FrozenInterner`]。
+    ///
+    /// 所有 Symbol 在凍結前後保持不變，Arena 中的字符串也不會被複製。
+    pub fn freeze(self) -> FrozenInterner<S, MIN_ALIGN> {
+        let Interner {
+            shards,
+            table,
+            arena,
+            ..
+        } = self;
+        // 我們擁有 self，不可能再有並發的寫入方，所以所有預留的編號都已經發布了。
+        // 安全性：一個 `Interner<S, MIN_ALIGN, str>` 中的字節都來自 str。
+        let strings: Box<[&'static str]> = (0..table.len())
+            .map(|i| unsafe { str::from_utf8_unchecked(table.get_or_wait(i)) })
+            .collect();
+        drop(shards);
+        drop(table);
+
+        let sorted = SortedIndex::build(strings.len(), |i| strings[i])
+            .unwrap_or_else(|_| unreachable!("an interner never holds duplicate strings"));
+        FrozenInterner {
+            strings,
+            sorted,
+            _arena: arena,
+        }
+    }
+}
+
+impl<S: SymbolRepr, const MIN_ALIGN: usize> FrozenInterner<S, MIN_ALIGN> {
+    /// 根據 Symbol 取回字符串；如果 Symbol 不屬於這張表，返回 None。
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        self.strings.get(symbol.index()).copied()
+    }
+
+    /// 查找一個字符串的 Symbol；不在表中的字符串返回 None。
+    pub fn get(&self, s: &str) -> Option<S> {
+        self.sorted.find(s, |i| self.strings[i])
+    }
+
+    /// 檢查字符串是否在表中。
+    pub fn contains(&self, s: &str) -> bool {
+        self.get(s).is_some()
+    }
+
+    /// 返回表中字符串的數量。
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// 檢查表是否為空。
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// 按 Symbol 編號的順序遍歷所有字符串。
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (S, &str)> + '_ {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(index, s)| (S::from_index(index), *s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze_keeps_symbols() {
+        let interner: Interner = Interner::with_capacity_and_shards(16, 4);
+        let words = ["let", "fn", "", "x", "数据"];
+        let symbols: Vec<Symbol> = words.iter().map(|s| interner.intern(s)).collect();
+        let static_sym = interner.intern_static("static");
+
+        let frozen = interner.freeze();
+        assert_eq!(frozen.len(), words.len() + 1);
+        for (sym, word) in symbols.iter().zip(words) {
+            assert_eq!(frozen.resolve(*sym), Some(word));
+            assert_eq!(frozen.get(word), Some(*sym));
+        }
+        assert_eq!(frozen.get("static"), Some(static_sym));
+        assert_eq!(frozen.get("missing"), None);
+        assert!(!frozen.contains("le"));
+        assert_eq!(frozen.resolve(Symbol::from_u32(100)), None);
+
+        let listed: Vec<(Symbol, &str)> = frozen.iter().collect();
+        assert_eq!(listed.len(), 6);
+        assert_eq!(listed[0], (symbols[0], "let"));
+        assert_eq!(listed[5], (static_sym, "static"));
+    }
+
+    #[test]
+    fn test_frozen_interner_is_shared_across_threads() {
+        use std::thread;
+
+        fn assert_sync<T: Sync + Send>(_: &T) {}
+
+        let interner: Interner = Interner::new();
+        let words: Vec<String> = (0..100).map(|i| format!("w{i}")).collect();
+        for word in &words {
+            interner.intern(word);
+        }
+        let frozen = interner.freeze();
+        assert_sync(&frozen);
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for word in &words {
+                        let sym = frozen.get(word).unwrap();
+                        assert_eq!(frozen.resolve(sym), Some(word.as_str()));
+                    }
+                });
+            }
+        });
+    }
+}
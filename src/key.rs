@@ -0,0 +1,92 @@
+// src/key.rs
+
+//! 可以被駐留的鍵類型。
+//!
+//! [`Interner`](crate::Interner) 的第三個泛型參數決定了它駐留什麼：默認是 `str`，
+//! 也可以是 `OsStr`、`Path`，或者任意元素類型的切片 `[T]`（比如 `[u8]`、`[u32]`、`[Symbol]`）。
+//! 不論是哪一種，池中保存的都是它的元素切片表示，所以它們共享同一套 Arena、
+//! 分片查找表和 Symbol 編號的實現。
+
+use std::ffi::OsStr;
+use std::hash::Hash;
+use std::path::Path;
+
+/// 可以被 [`Interner`](crate::Interner) 駐留的類型。
+///
+/// 一個鍵必須能無損地轉換為一段 `Elem` 切片，並且能從同一段切片還原。
+/// 池中的相等性和哈希都按這段切片計算：例如 `Path::new("a//b")` 和 `Path::new("a/b")`
+/// 雖然作為 `Path` 相等，卻會得到不同的 Symbol，解析時也會原樣返回。
+///
+/// # Safety
+/// 對任意 `key`，`from_slice(key.as_slice())` 必須是合法的，並且返回一個與 `key` 等價的值。
+pub unsafe trait Internable: 'static {
+    /// 切片表示的元素類型。它會被按位複製進 Arena，所以必須是 `Copy` 的。
+    type Elem: Copy + Hash + Eq + 'static;
+
+    /// 返回這個鍵的切片表示。
+    fn as_slice(&self) -> &[Self::Elem];
+
+    /// 從切片表示還原一個鍵。
+    ///
+    /// # Safety
+    /// `slice` 必須是同一類型的某個值在當前進程中通過 [`Internable::as_slice`] 得到的。
+    unsafe fn from_slice(slice: &[Self::Elem]) -> &Self;
+}
+
+unsafe impl Internable for str {
+    type Elem = u8;
+
+    #[inline]
+    fn as_slice(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    #[inline]
+    unsafe fn from_slice(slice: &[u8]) -> &Self {
+        // 安全性：字節來自一個 str，所以一定是合法的 UTF-8。
+        unsafe { str::from_utf8_unchecked(slice) }
+    }
+}
+
+unsafe impl<T: Copy + Hash + Eq + 'static> Internable for [T] {
+    type Elem = T;
+
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    #[inline]
+    unsafe fn from_slice(slice: &[T]) -> &Self {
+        slice
+    }
+}
+
+unsafe impl Internable for OsStr {
+    type Elem = u8;
+
+    #[inline]
+    fn as_slice(&self) -> &[u8] {
+        self.as_encoded_bytes()
+    }
+
+    #[inline]
+    unsafe fn from_slice(slice: &[u8]) -> &Self {
+        // 安全性：字節來自同一進程中的 `OsStr::as_encoded_bytes`。
+        unsafe { OsStr::from_encoded_bytes_unchecked(slice) }
+    }
+}
+
+unsafe impl Internable for Path {
+    type Elem = u8;
+
+    #[inline]
+    fn as_slice(&self) -> &[u8] {
+        self.as_os_str().as_encoded_bytes()
+    }
+
+    #[inline]
+    unsafe fn from_slice(slice: &[u8]) -> &Self {
+        Path::new(unsafe { <OsStr as Internable>::from_slice(slice) })
+    }
+}
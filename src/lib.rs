@@ -1,100 +1,397 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "allocator-api2")]
+mod allocator_api2_impl;
 mod chunkfooter;
+mod error;
+mod frozen;
+mod key;
+mod localbump;
+mod macros;
+mod mapped;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod snapshot;
+mod symbol;
 mod syncbump;
+mod table;
+mod typed;
 
-use rustc_hash::{FxHashMap, FxBuildHasher};
+pub use error::{InternError, SnapshotError};
+pub use frozen::FrozenInterner;
+pub use key::Internable;
+pub use localbump::{DEFAULT_LOCAL_BLOCK_SIZE, LocalBump};
+pub use mapped::MappedInterner;
+#[cfg(feature = "serde")]
+pub use serde_impl::{InternSeed, ResolvedSymbol};
+pub use symbol::{Symbol, Symbol16, Symbol64, SymbolRepr};
+pub use syncbump::{AllocErr, ArenaStats, ChunkIter, ChunkRawIter, SyncBump};
+pub use typed::{TypedInterner, TypedSymbol};
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
+use std::alloc::Layout;
 use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+use std::path::Path;
 use std::sync::RwLock;
-use syncbump::SyncBump;
-
-// --- 符號 (Symbol) 類型 ---
-/// 一個輕量級的、唯一的字符串標識符。
-/// 它可以被高效地複製、傳遞、比較和用作哈希表的鍵。
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Symbol(u32);
+use table::SymbolTable;
 
 // --- 內部數據結構 ---
-/// 這個結構體包含了所有需要被鎖保護的共享數據。
-/// 把它們放在一個單獨的結構體中，可以讓鎖的管理更清晰。
-struct InternerData<'a> {
-    /// 從 &str 快速查找到對應的 Symbol。
-    map: FxHashMap<&'a str, Symbol>,
-    /// 從 Symbol 快速查找到對應的 &str。
-    /// Symbol 的 u32 值就是這個 Vec 的索引。
-    vec: Vec<&'a str>,
-}
+/// 一個分片：被自己的讀寫鎖保護的「鍵的切片表示 -> Symbol」查找表。
+/// 鍵的哈希值決定它屬於哪一個分片，不同分片上的寫入互不阻塞。
+///
+/// 這裡的 `'static` 是一個內部的「謊言」：切片實際上住在 `Interner::arena` 裡，
+/// 只要 Interner 還活著就一直有效。對外暴露時，生命週期總是會被縮短到 `&self`。
+type Shard<S, E> = RwLock<FxHashMap<&'static [E], S>>;
 
 // --- Interner 主結構體 ---
-/// 一個線程安全的、基於 Bump Allocator 的全局字符串駐留池。
-pub struct Interner<'bump, const MIN_ALIGN: usize = 1> {
+/// 一個線程安全的、基於 Bump Allocator 的字符串駐留池。
+///
+/// Interner 擁有自己的 Arena，不再需要自引用的 `'bump` 生命週期：
+/// 它既可以放在 `static` 裡全局共享，也可以放在棧上或 `Arc` 裡，
+/// 隨著一次編譯會話結束而整體釋放。
+///
+/// 查找表可以被切分為多個獨立加鎖的分片（見 [`Interner::with_capacity_and_shards`]），
+/// 而 Symbol 的編號仍然是全局唯一、連續分配的。
+///
+/// `S` 決定 Symbol 的寬度，默認是 4 字節的 [`Symbol`]，見 [`SymbolRepr`]。
+/// `K` 決定駐留的鍵類型，默認是 `str`；[`ByteInterner`]、[`OsStrInterner`] 和 [`PathInterner`]
+/// 分別駐留原始字節、`OsStr` 和 `Path`，[`SliceInterner`] 則駐留任意 `Copy + Hash + Eq`
+/// 元素的切片，見 [`Internable`]。
+pub struct Interner<S = Symbol, const MIN_ALIGN: usize = 1, K: ?Sized + Internable = str> {
+    /// 從鍵快速查找到對應的 Symbol，按哈希分片。
+    /// 分片數量總是 2 的冪，這樣可以用掩碼代替取模。
+    /// 注意：字段按聲明順序析構，查找表必須先於 Arena 被釋放。
+    shards: Box<[Shard<S, K::Elem>]>,
+    /// 從 Symbol 快速查找到對應的鍵。
+    /// Symbol 的編號就是這張表的下標，它由所有分片共享。
+    /// 這是一張只追加、用原子操作發布的分段表，所以 `resolve` 完全不需要加鎖。
+    table: SymbolTable<K::Elem>,
     /// 底層的 Bump Allocator，負責實際的內存分配。
     arena: SyncBump<MIN_ALIGN>,
-    /// 被讀寫鎖保護的核心數據（查找表）。
-    data: RwLock<InternerData<'bump>>,
+    /// 由 [`Interner::with_predefined`] 駐留的前綴長度。這些鍵指向 `'static` 數據，
+    /// 它們的編號與 [`symbols!`] 生成的常量綁定，[`Interner::clear`] 會保留它們。
+    predefined: usize,
+    /// 鍵的類型只存在於接口上，池中保存的都是它的切片表示。
+    _key: PhantomData<fn() -> K>,
+}
+
+/// 駐留原始字節串的 Interner，用於二進制格式中的記號等不一定是 UTF-8 的數據。
+///
+/// 除了通用的 [`Interner::intern`]，它還提供了 [`Interner::intern_bytes`]、
+/// [`Interner::intern_os_str`] 和 [`Interner::intern_path`]（以及對應的 `resolve_*`），
+/// 讓字節串、`OsStr` 和路徑共享同一個 Arena 和同一套 Symbol 編號。
+/// 只需要其中一種鍵時，也可以使用 [`OsStrInterner`] 或 [`PathInterner`]。
+///
+/// ```
+/// use interb::ByteInterner;
+/// use std::ffi::OsStr;
+/// use std::path::Path;
+///
+/// let interner: ByteInterner = ByteInterner::default();
+/// let raw = interner.intern_bytes(b"src/lib.rs");
+/// let path = interner.intern_path(Path::new("src/lib.rs"));
+/// assert_eq!(raw, path);
+/// assert_eq!(interner.resolve_os_str(path), Some(OsStr::new("src/lib.rs")));
+/// assert_eq!(interner.resolve_bytes(path), Some(&b"src/lib.rs"[..]));
+/// ```
+pub type ByteInterner<S = Symbol, const MIN_ALIGN: usize = 1> = Interner<S, MIN_ALIGN, [u8]>;
+
+/// 駐留 `OsStr` 的 Interner，例如命令行參數和環境變量。
+pub type OsStrInterner<S = Symbol, const MIN_ALIGN: usize = 1> = Interner<S, MIN_ALIGN, OsStr>;
+
+/// 駐留任意元素切片的 Interner，例如 `[u32]` 表示的類型簽名，或 `[Symbol]` 表示的路徑。
+///
+/// ```
+/// use interb::{SliceInterner, Symbol};
+///
+/// let paths: SliceInterner<Symbol> = SliceInterner::default();
+/// let std_hash_map = [Symbol::from_u32(0), Symbol::from_u32(1), Symbol::from_u32(2)];
+/// let path = paths.intern(&std_hash_map);
+/// assert_eq!(paths.intern(&std_hash_map[..]), path);
+/// assert_eq!(paths.resolve(path), Some(&std_hash_map[..]));
+/// ```
+pub type SliceInterner<T, S = Symbol, const MIN_ALIGN: usize = 1> = Interner<S, MIN_ALIGN, [T]>;
+
+/// 駐留文件路徑的 Interner。
+///
+/// 路徑按字節比較，不做任何規範化：`a/b` 和 `a//b` 會得到不同的 Symbol。
+pub type PathInterner<S = Symbol, const MIN_ALIGN: usize = 1> = Interner<S, MIN_ALIGN, Path>;
+
+impl Interner {
+    /// 創建一個空的 Interner，第一次駐留時才會分配內存。
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: SymbolRepr, const MIN_ALIGN: usize, K: ?Sized + Internable> Default
+    for Interner<S, MIN_ALIGN, K>
+{
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
 }
 
-impl<'bump, const MIN_ALIGN: usize> Interner<'bump, MIN_ALIGN> {
+impl<S: SymbolRepr, const MIN_ALIGN: usize, K: ?Sized + Internable> Interner<S, MIN_ALIGN, K> {
     /// 創建一個帶有預設容量的 Interner，以提高性能。
+    /// 查找表只有一個分片；高並發寫入的場景請使用 [`Interner::with_capacity_and_shards`]。
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_shards(capacity, 1)
+    }
+
+    /// 創建一個帶有預設容量、並把查找表切分為 `shards` 個分片的 Interner。
+    ///
+    /// `shards` 會被向上取整到 2 的冪（至少為 1）。每個分片有自己的讀寫鎖，
+    /// 所以落在不同分片上的未命中可以並行插入，而不是全部排隊等待同一把寫鎖。
+    pub fn with_capacity_and_shards(capacity: usize, shards: usize) -> Self {
+        let arena = SyncBump::<MIN_ALIGN>::with_capacity(capacity * 10); // 假設平均字符串長度為10
+        Self::with_arena(capacity, shards, arena)
+    }
+
+    /// 用一個已經準備好的 Arena 組裝 Interner，查找表預留 `capacity` 個位置。
+    fn with_arena(capacity: usize, shards: usize, arena: SyncBump<MIN_ALIGN>) -> Self {
+        let shard_count = shards.max(1).next_power_of_two();
+        let capacity_per_shard = capacity.div_ceil(shard_count);
         Interner {
-            arena: SyncBump::<MIN_ALIGN>::with_capacity(capacity * 10), // 假設平均字符串長度為10
-            data: RwLock::new(InternerData {
-                map: HashMap::with_capacity_and_hasher(capacity, FxBuildHasher::default()),
-                vec: Vec::with_capacity(capacity),
-            }),
+            shards: (0..shard_count)
+                .map(|_| {
+                    RwLock::new(HashMap::with_capacity_and_hasher(
+                        capacity_per_shard,
+                        FxBuildHasher,
+                    ))
+                })
+                .collect(),
+            table: SymbolTable::new(),
+            arena,
+            predefined: 0,
+            _key: PhantomData,
         }
     }
 
+    /// 返回查找表的分片數量。
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// 根據鍵的切片表示的哈希值選出它所屬的分片下標。
+    /// 使用哈希的高位，因為低位會被分片內部的 HashMap 用來選桶。
+    #[inline]
+    fn shard_index(&self, items: &[K::Elem]) -> usize {
+        let hash = FxBuildHasher.hash_one(items);
+        (hash >> 32) as usize & (self.shards.len() - 1)
+    }
+
     /// 將一個字符串存入池中，返回其唯一的 Symbol。
     /// 如果字符串已存在，則返回現有的 Symbol；否則，會分配新內存並創建新的 Symbol。
-    pub fn intern(&'bump self, s: &str) -> Symbol {
+    ///
+    /// # Panics
+    /// Symbol 編號耗盡或底層 Arena 分配失敗時會 panic，
+    /// 需要優雅處理這些情況時請使用 [`Interner::try_intern`]。
+    pub fn intern(&self, key: &K) -> S {
+        self.try_intern(key)
+            .unwrap_or_else(|err| intern_failed(err))
+    }
+
+    /// [`Interner::intern`] 的可失敗版本。
+    ///
+    /// 與 `intern` 不同，Symbol 編號耗盡、Arena 分配失敗或超出分配上限時，
+    /// 這裡會返回對應的 [`InternError`]，並且不會修改池中的任何內容。
+    pub fn try_intern(&self, key: &K) -> Result<S, InternError> {
+        let items = key.as_slice();
+        self.intern_with(items, || self.alloc_in_arena(items))
+    }
+
+    /// 駐留一個 `'static` 字符串，不做任何複製。
+    ///
+    /// 如果字符串還不在池中，池會直接保存這個借用的指針，而不會把它複製到 Arena 裡，
+    /// 所以 [`Interner::memory_usage`] 只反映動態駐留的字符串。
+    /// 如果字符串已經存在（無論是否是通過 `intern_static` 駐留的），返回現有的 Symbol。
+    ///
+    /// # Panics
+    /// Symbol 編號耗盡時會 panic。
+    pub fn intern_static(&self, key: &'static K) -> S {
+        let items = key.as_slice();
+        self.intern_with(items, || Ok(items))
+            .unwrap_or_else(|err| intern_failed(err))
+    }
+
+    /// 駐留路徑的公共部分：先在讀鎖下查找，未命中時在寫鎖下插入。
+    /// `stable` 負責提供一個有穩定地址的切片副本，只有在確認需要插入時才會被調用。
+    fn intern_with<F>(&self, items: &[K::Elem], stable: F) -> Result<S, InternError>
+    where
+        F: FnOnce() -> Result<&'static [K::Elem], InternError>,
+    {
+        let shard = &self.shards[self.shard_index(items)];
+
         // --- 快速讀取路徑 ---
-        // 1. 獲取讀鎖，檢查字符串是否已存在。
-        let read_guard = self.data.read().expect("RwLock poisoned during read");
-        if let Some(symbol) = read_guard.map.get(s) {
-            return *symbol;
+        // 1. 獲取分片的讀鎖，檢查字符串是否已存在。
+        let read_guard = shard.read().expect("RwLock poisoned during read");
+        if let Some(symbol) = read_guard.get(items) {
+            return Ok(*symbol);
         }
         drop(read_guard); // 顯式釋放讀鎖，為接下來的寫鎖做準備
 
         // --- 慢速寫入路徑 ---
-        // 2. 獲取寫鎖。
-        let mut write_guard = self.data.write().unwrap();
+        // 2. 獲取分片的寫鎖，其他分片上的讀寫不受影響。
+        let mut write_guard = shard.write().unwrap();
+
+        // 3. 在寫鎖的保護下完成插入（內部會做雙重檢查）。
+        self.insert_locked(&mut write_guard, items, stable)
+    }
+
+    /// 批量駐留：把 `keys` 中每個鍵的 Symbol 依次追加到 `out` 的末尾。
+    ///
+    /// 與逐個調用 [`Interner::intern`] 相比，這裡每個涉及到的分片只獲取一次讀鎖來解析所有命中，
+    /// 然後（如果有未命中）只獲取一次寫鎖來插入所有缺失的鍵，
+    /// 避免了「讀鎖 → 釋放 → 寫鎖」在每次未命中時的反覆切換。
+    /// 在只有一個分片時，整個批次恰好是一次讀鎖加至多一次寫鎖。
+    ///
+    /// # Panics
+    /// 與 [`Interner::intern`] 相同。
+    pub fn intern_many<'s, I>(&self, keys: I, out: &mut Vec<S>)
+    where
+        I: IntoIterator<Item = &'s K>,
+    {
+        // 先按分片分組：同一時刻只持有一個分片的鎖，避免多個批次之間交叉加鎖導致死鎖。
+        let base = out.len();
+        let mut pending: Vec<(usize, usize, &'s [K::Elem])> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let items = key.as_slice();
+                (self.shard_index(items), base + i, items)
+            })
+            .collect();
+        // 結果的位置已經確定，先用佔位 Symbol 填上。
+        out.resize(base + pending.len(), S::from_index(0));
+        pending.sort_by_key(|&(shard, _, _)| shard);
 
-        // 3. 雙重檢查！在等待寫鎖時，可能已有其他線程完成了插入。
-        if let Some(symbol) = write_guard.map.get(s) {
-            return *symbol;
+        let mut misses: Vec<(usize, &'s [K::Elem])> = Vec::new();
+        for group in pending.chunk_by(|a, b| a.0 == b.0) {
+            let shard = &self.shards[group[0].0];
+
+            // --- 第一階段：一次讀鎖，解析這個分片上的所有命中 ---
+            {
+                let read_guard = shard.read().expect("RwLock poisoned during read");
+                for &(_, index, s) in group {
+                    match read_guard.get(s) {
+                        Some(symbol) => out[index] = *symbol,
+                        None => misses.push((index, s)),
+                    }
+                }
+            }
+
+            if misses.is_empty() {
+                continue;
+            }
+
+            // --- 第二階段：一次寫鎖，插入這個分片上的所有未命中 ---
+            // 批次內部的重複鍵和其他線程的並發插入，都由 insert_locked 的雙重檢查處理。
+            let mut write_guard = shard.write().unwrap();
+            for (index, s) in misses.drain(..) {
+                out[index] = self
+                    .insert_locked(&mut write_guard, s, || self.alloc_in_arena(s))
+                    .unwrap_or_else(|err| intern_failed(err));
+            }
+        }
+    }
+
+    /// 在已經持有分片寫鎖的前提下駐留一個鍵。
+    /// 調用者在等待寫鎖期間，可能已有其他線程完成了插入，所以這裡必須先雙重檢查。
+    fn insert_locked<F>(
+        &self,
+        map: &mut FxHashMap<&'static [K::Elem], S>,
+        items: &[K::Elem],
+        stable: F,
+    ) -> Result<S, InternError>
+    where
+        F: FnOnce() -> Result<&'static [K::Elem], InternError>,
+    {
+        if let Some(symbol) = map.get(items) {
+            return Ok(*symbol);
+        }
+
+        // 編號已滿時在分配之前就失敗，避免把注定發布不了的鍵複製進 Arena。
+        // 這只是一個提前的檢查，真正決定成敗的是 publish_locked 中帶上限的 `try_push`：
+        // 只有一個分片、或者沒有其他線程同時在插入時，失敗的駐留才保證不會讓 Arena 增長。
+        // 其他分片上的線程可能在這裡和 `try_push` 之間佔用最後的編號，
+        // 這時已經複製進 Arena 的鍵會一直留到 Interner 被清空或析構。
+        // （不能先預留編號再分配：分配失敗時，被預留的編號就永遠無法發布了。）
+        if self.table.len() >= S::MAX_COUNT {
+            return Err(InternError::SymbolOverflow);
         }
 
-        // 4. 確認沒有，執行真正的分配和插入。
-        let id = write_guard.vec.len() as u32;
-        let symbol = Symbol(id);
+        // 確認沒有，執行真正的分配和插入。
+        let interned = stable()?;
+        self.publish_locked(map, interned)
+    }
 
-        // 使用 arena 分配一個生命週期為 'bump 的字符串
-        let interned_str = self.arena.alloc_str(s);
+    /// 使用 arena 複製一個切片，並把它的生命週期擴展為 'static。
+    /// 安全性：Arena 中的內存在 Interner 被析構之前永遠不會被釋放或移動，
+    /// 而所有對外返回的引用都被綁定在 &self 上。
+    fn alloc_in_arena(&self, items: &[K::Elem]) -> Result<&'static [K::Elem], InternError> {
+        match self.arena.try_alloc_slice_copy(items) {
+            Ok(interned) => Ok(unsafe { &*(interned as *const [K::Elem]) }),
+            Err(err) => Err(
+                if self.arena.allocation_limit_blocks(Layout::for_value(items)) {
+                    InternError::AllocationLimitExceeded
+                } else {
+                    InternError::Alloc(err)
+                },
+            ),
+        }
+    }
 
-        // 同時更新 vec 和 map，保持數據一致性
-        write_guard.vec.push(interned_str);
-        write_guard.map.insert(interned_str, symbol);
+    /// 在已經持有分片寫鎖、並且確認鍵不在池中的前提下，
+    /// 為一個已經有穩定地址的切片分配編號，並把它發布到符號表和分片中。
+    fn publish_locked(
+        &self,
+        map: &mut FxHashMap<&'static [K::Elem], S>,
+        interned: &'static [K::Elem],
+    ) -> Result<S, InternError> {
+        // 在全局的符號表上分配編號並發布字符串，這一步是無鎖的。
+        // 必須先發布再寫入分片：任何從分片中拿到這個 Symbol 的線程，都一定能 resolve 它。
+        let index = self
+            .table
+            .try_push(interned, S::MAX_COUNT)
+            .ok_or(InternError::SymbolOverflow)?;
+        let symbol = S::from_index(index);
+        map.insert(interned, symbol);
 
-        symbol
+        Ok(symbol)
     }
 
-    /// 根據 Symbol，獲取其對應的字符串切片。
+    /// 只查找、不插入：如果鍵已經在池中，返回其 Symbol；否則返回 None。
+    /// 這條路徑只會獲取讀鎖，永遠不會觸碰底層的 Arena。
+    pub fn get(&self, key: &K) -> Option<S> {
+        let items = key.as_slice();
+        let shard = &self.shards[self.shard_index(items)];
+        let read_guard = shard.read().expect("RwLock poisoned during read");
+        read_guard.get(items).copied()
+    }
+
+    /// 檢查鍵是否已經被駐留。與 [`Interner::get`] 一樣不會分配內存。
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// 根據 Symbol，獲取其對應的字符串切片（或其他類型的鍵）。
     /// 如果 Symbol 無效，返回 None。
-    pub fn resolve(&self, symbol: Symbol) -> Option<&'bump str> {
-        let read_guard = self.data.read().expect("RwLock poisoned during read");
-        // 將 u32 索引轉換為 usize，並安全地訪問 Vec
-        read_guard.vec.get(symbol.0 as usize).copied()
+    ///
+    /// 這條路徑是 wait-free 的：它不獲取任何鎖，即使此時有寫入方正持有分片的寫鎖。
+    pub fn resolve(&self, symbol: S) -> Option<&K> {
+        // 安全性：表中的切片都來自同一類型 `K` 的鍵。
+        self.table
+            .get(symbol.index())
+            .map(|items| unsafe { K::from_slice(items) })
     }
 
     /// 返回池中獨立字符串的數量。
     pub fn len(&self) -> usize {
-        self.data
-            .read()
-            .expect("RwLock poisoned during read")
-            .vec
-            .len()
+        self.table.len()
     }
 
     /// 检查池中是否没有任何字符串。
@@ -102,10 +399,224 @@ impl<'bump, const MIN_ALIGN: usize> Interner<'bump, MIN_ALIGN> {
         self.len() == 0
     }
 
+    /// 按 Symbol 編號的順序遍歷池中的所有字符串。
+    ///
+    /// 迭代器在創建時記下當前的長度，只會產出在那一刻已經存在的字符串：
+    /// 其他線程可以在遍歷期間繼續駐留，新的字符串不會出現在這次遍歷中，
+    /// 已經產出的 `(Symbol, &str)` 也永遠不會失效。
+    pub fn iter(&self) -> Iter<'_, S, K> {
+        Iter {
+            table: &self.table,
+            next: 0,
+            end: self.table.len(),
+            _symbol: PhantomData,
+            _key: PhantomData,
+        }
+    }
+
     /// 返回 Interner 底層 Arena 已分配的總內存字節數。
+    ///
+    /// 這是所有 chunk 的總容量，而不是其中被使用的部分；需要更詳細的數字時使用 [`Interner::arena_stats`]。
     pub fn memory_usage(&self) -> usize {
         self.arena.allocated_bytes()
     }
+
+    /// 返回底層 Arena 的詳細內存統計，見 [`ArenaStats`]。
+    ///
+    /// [`Interner::memory_usage`] 報告的是 Arena 的總容量，這裡還能看到其中有多少被真正使用、
+    /// 有多少被浪費。統計不包括查找表本身。
+    pub fn arena_stats(&self) -> ArenaStats {
+        self.arena.stats()
+    }
+
+    /// 返回底層 Arena 的分配上限，`None` 表示無上限。
+    pub fn allocation_limit(&self) -> Option<usize> {
+        self.arena.allocation_limit()
+    }
+
+    /// 設置底層 Arena 的分配上限（字節），用於限制不可信的輸入最多能讓 Interner 佔用多少內存。
+    ///
+    /// 上限計算的是 Arena 中所有 chunk 的總容量（與 [`Interner::memory_usage`] 相同），
+    /// 不包括查找表本身。達到上限之後，[`Interner::try_intern`] 會返回
+    /// [`InternError::AllocationLimitExceeded`]，而 [`Interner::intern`] 會 panic；
+    /// 已經駐留的字符串仍然可以正常查找和解析。
+    pub fn set_allocation_limit(&self, limit: Option<usize>) {
+        self.arena.set_allocation_limit(limit);
+    }
+
+    /// 清空池中的所有字符串，並重置底層 Arena 以便重用內存。
+    ///
+    /// Arena 只保留容量最大的那個 chunk（見 [`SyncBump::reset`]），查找表保留已有的容量，
+    /// 分片數量和分配上限保持不變。
+    ///
+    /// 由 [`Interner::with_predefined`] 預定義的字符串會被保留，編號不變，
+    /// 所以 [`symbols!`] 生成的常量在清空之後仍然有效。其他字符串（包括之後通過
+    /// [`Interner::intern_static`] 駐留的）都會被移除，之後駐留的字符串緊接著預定義的前綴重新分配編號，
+    /// 所以清空之前得到的其他 Symbol 不應該再被使用：它們可能解析為 None，也可能解析為另一個字符串。
+    pub fn clear(&mut self) {
+        // 預定義的切片指向 'static 數據而不是 Arena，重置之後可以原樣重新發布。
+        let predefined: Vec<&'static [K::Elem]> = (0..self.predefined)
+            .map(|index| self.table.get_or_wait(index))
+            .collect();
+
+        // 查找表和符號表中的切片指向 Arena，必須先清空它們，再重置 Arena。
+        for shard in self.shards.iter_mut() {
+            shard.get_mut().unwrap().clear();
+        }
+        self.table = SymbolTable::new();
+        self.arena.reset();
+
+        for items in predefined {
+            let index = self
+                .table
+                .try_push(items, S::MAX_COUNT)
+                .expect("BUG: predefined symbols fitted before clearing");
+            let shard = self.shard_index(items);
+            self.shards[shard]
+                .get_mut()
+                .unwrap()
+                .insert(items, S::from_index(index));
+        }
+    }
+}
+
+impl<S: SymbolRepr, const MIN_ALIGN: usize> Interner<S, MIN_ALIGN> {
+    /// 創建一個預先駐留了 `predefined` 中所有字符串的 Interner。
+    ///
+    /// 這些字符串按順序得到編號 `0, 1, 2, ...`，與 [`symbols!`] 生成的常量一一對應，
+    /// 並且直接指向傳入的 `'static` 字符串，不會被複製到 Arena 中。
+    ///
+    /// # Panics
+    /// 如果 `predefined` 中有重複的字符串（這會讓後面的編號全部錯位），會 panic。
+    pub fn with_predefined(predefined: &[&'static str]) -> Self {
+        // 預定義的字符串不會被複製進 Arena，所以只為查找表預留容量，Arena 保持為空。
+        let mut interner = Self::with_arena(predefined.len(), 1, SyncBump::default());
+        for (index, &s) in predefined.iter().enumerate() {
+            let shard = &interner.shards[interner.shard_index(s.as_bytes())];
+            let mut map = shard.write().unwrap();
+            assert!(
+                !map.contains_key(s.as_bytes()),
+                "duplicate predefined symbol {s:?} at index {index}"
+            );
+            let symbol = interner
+                .publish_locked(&mut map, s.as_bytes())
+                .unwrap_or_else(|err| intern_failed(err));
+            debug_assert_eq!(symbol.index(), index);
+        }
+        interner.predefined = predefined.len();
+        interner
+    }
+}
+
+/// 原始字節串、`OsStr` 和 `Path` 的入口。
+///
+/// 三者都按字節駐留在同一個 [`ByteInterner`] 中，共享它的 Arena 和 Symbol 編號：
+/// 字節相同的字節串、`OsStr` 和路徑會得到同一個 Symbol。
+impl<S: SymbolRepr, const MIN_ALIGN: usize> Interner<S, MIN_ALIGN, [u8]> {
+    /// 駐留一個字節串，與 [`Interner::intern`] 相同。
+    ///
+    /// # Panics
+    /// 與 [`Interner::intern`] 相同。
+    pub fn intern_bytes(&self, bytes: &[u8]) -> S {
+        self.intern(bytes)
+    }
+
+    /// 按 `OsStr::as_encoded_bytes` 駐留一個 `OsStr`。
+    ///
+    /// # Panics
+    /// 與 [`Interner::intern`] 相同。
+    pub fn intern_os_str(&self, s: &OsStr) -> S {
+        self.intern(s.as_encoded_bytes())
+    }
+
+    /// 駐留一個路徑。路徑按字節比較，不做任何規範化：`a/b` 和 `a//b` 會得到不同的 Symbol。
+    ///
+    /// # Panics
+    /// 與 [`Interner::intern`] 相同。
+    pub fn intern_path(&self, path: &Path) -> S {
+        self.intern_os_str(path.as_os_str())
+    }
+
+    /// 根據 Symbol 取回字節串，與 [`Interner::resolve`] 相同。
+    pub fn resolve_bytes(&self, symbol: S) -> Option<&[u8]> {
+        self.resolve(symbol)
+    }
+
+    /// 根據 Symbol 取回 `OsStr`。
+    ///
+    /// 在 Unix 上任意字節都是合法的 `OsStr`。其他平台上 `OsStr` 的字節表示並不公開，
+    /// 這裡只能接受 UTF-8：如果這個 Symbol 是由非 UTF-8 的字節駐留的，會返回 None。
+    pub fn resolve_os_str(&self, symbol: S) -> Option<&OsStr> {
+        os_str_from_bytes(self.resolve(symbol)?)
+    }
+
+    /// 根據 Symbol 取回路徑，規則與 [`Interner::resolve_os_str`] 相同。
+    pub fn resolve_path(&self, symbol: S) -> Option<&Path> {
+        self.resolve_os_str(symbol).map(Path::new)
+    }
+}
+
+/// 把任意字節安全地轉換為 `OsStr`。
+#[cfg(unix)]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(OsStr::from_bytes(bytes))
+}
+
+/// 把任意字節安全地轉換為 `OsStr`：不是 UTF-8 的字節無法確定是否合法，只能拒絕。
+#[cfg(not(unix))]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    str::from_utf8(bytes).ok().map(OsStr::new)
+}
+
+impl<'a, S: SymbolRepr, const MIN_ALIGN: usize, K: ?Sized + Internable> IntoIterator
+    for &'a Interner<S, MIN_ALIGN, K>
+{
+    type Item = (S, &'a K);
+    type IntoIter = Iter<'a, S, K>;
+
+    fn into_iter(self) -> Iter<'a, S, K> {
+        self.iter()
+    }
+}
+
+/// 按 Symbol 編號順序遍歷 Interner 的迭代器，由 [`Interner::iter`] 創建。
+pub struct Iter<'a, S = Symbol, K: ?Sized + Internable = str> {
+    table: &'a SymbolTable<K::Elem>,
+    /// 下一個要產出的編號。
+    next: usize,
+    /// 創建迭代器時觀察到的長度，遍歷不會超過它。
+    end: usize,
+    _symbol: PhantomData<fn() -> S>,
+    _key: PhantomData<&'a K>,
+}
+
+impl<'a, S: SymbolRepr, K: ?Sized + Internable> Iterator for Iter<'a, S, K> {
+    type Item = (S, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        let index = self.next;
+        self.next += 1;
+        // 編號在 `end` 之內說明它已經被預留，最多只需要等它被發布。
+        let items = self.table.get_or_wait(index);
+        Some((S::from_index(index), unsafe { K::from_slice(items) }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S: SymbolRepr, K: ?Sized + Internable> ExactSizeIterator for Iter<'_, S, K> {}
+
+#[cold]
+#[inline(never)]
+fn intern_failed(err: InternError) -> ! {
+    panic!("failed to intern string: {err}")
 }
 
 #[cfg(test)]
@@ -115,11 +626,9 @@ mod tests {
 
     #[test]
     fn test_intern_and_resolve_basic() {
-        // Interner 需要一个生命周期 'bump。在测试中，一个简单的方法是
-        // 创建一个 static 的 Interner，这样它的生命周期就是 'static。
-        // once_cell 是做这个的常用库。
+        // 最常见的用法：用 once_cell 创建一个全局的 static Interner。
 
-        static INTERNER: Lazy<Interner<'static>> = Lazy::new(|| Interner::with_capacity(32));
+        static INTERNER: Lazy<Interner> = Lazy::new(|| Interner::with_capacity(32));
 
         let s1 = "hello";
         let sym1 = INTERNER.intern(s1);
@@ -133,7 +642,7 @@ mod tests {
 
     #[test]
     fn test_intern_uniqueness() {
-        static INTERNER: Lazy<Interner<'static>> = Lazy::new(|| Interner::with_capacity(32));
+        static INTERNER: Lazy<Interner> = Lazy::new(|| Interner::with_capacity(32));
 
         let s = "world";
         let sym1 = INTERNER.intern(s);
@@ -156,7 +665,7 @@ mod tests {
 
     #[test]
     fn test_edge_cases() {
-        static INTERNER: Lazy<Interner<'static>> = Lazy::new(|| Interner::with_capacity(16));
+        static INTERNER: Lazy<Interner> = Lazy::new(|| Interner::with_capacity(16));
 
         // 测试空字符串
         let empty_sym = INTERNER.intern("");
@@ -164,16 +673,587 @@ mod tests {
         assert_eq!(INTERNER.len(), 1);
 
         // 测试一个从未被创建的 Symbol
-        let invalid_sym = Symbol(999);
+        let invalid_sym = Symbol::from_u32(999);
         assert_eq!(INTERNER.resolve(invalid_sym), None);
     }
 
+    #[test]
+    fn test_symbol_niche() {
+        use std::mem::size_of;
+
+        // NonZeroU32 的空位让 Option<Symbol> 不需要额外的判别字段
+        assert_eq!(size_of::<Symbol>(), 4);
+        assert_eq!(size_of::<Option<Symbol>>(), 4);
+
+        // 编号仍然从 0 开始
+        let interner: Interner = Interner::new();
+        let first = interner.intern("first");
+        let second = interner.intern("second");
+        assert_eq!(first.index(), 0);
+        assert_eq!(second.index(), 1);
+        assert_eq!(format!("{first:?}"), "Symbol(0)");
+        assert_eq!(interner.resolve(first), Some("first"));
+        assert_eq!(interner.resolve(second), Some("second"));
+        assert_eq!(Some(first), interner.get("first"));
+    }
+
+    #[test]
+    fn test_symbol_widths() {
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<Option<Symbol16>>(), 2);
+        assert_eq!(size_of::<Option<Symbol64>>(), 8);
+
+        let small: Interner<Symbol16> = Interner::with_capacity_and_shards(16, 4);
+        let large: Interner<Symbol64> = Interner::default();
+        for word in ["a", "b", "a", "c"] {
+            assert_eq!(small.intern(word).index(), large.intern(word).index());
+        }
+        let c = large.get("c").unwrap();
+        assert_eq!(c, Symbol64::from_u64(2));
+        assert_eq!(format!("{c:?}"), "Symbol64(2)");
+        assert_eq!(small.resolve(Symbol16::from_u16(1)), Some("b"));
+        let listed: Vec<(Symbol16, &str)> = small.iter().collect();
+        assert_eq!(listed[2], (Symbol16::from_u16(2), "c"));
+    }
+
+    #[test]
+    fn test_symbol16_overflow() {
+        let interner: Interner<Symbol16> = Interner::with_capacity(Symbol16::MAX_COUNT);
+        for i in 0..Symbol16::MAX_COUNT {
+            interner.intern(&i.to_string());
+        }
+        assert_eq!(interner.len(), u16::MAX as usize);
+
+        // 编号用完之后优雅地失败，并且不会为失败的字符串分配内存，重试也一样
+        let usage = interner.memory_usage();
+        let used = interner.arena_stats().used_bytes;
+        for _ in 0..3 {
+            assert_eq!(
+                interner.try_intern("overflow"),
+                Err(InternError::SymbolOverflow)
+            );
+        }
+        assert_eq!(interner.memory_usage(), usage);
+        assert_eq!(interner.arena_stats().used_bytes, used);
+        // 已有的字符串依然可以驻留和解析
+        assert!(!interner.contains("overflow"));
+        let last = interner.intern(&(u16::MAX - 1).to_string());
+        assert_eq!(last.as_u16(), u16::MAX - 1);
+        assert_eq!(interner.len(), u16::MAX as usize);
+    }
+
+    #[test]
+    fn test_byte_os_str_and_path_interners() {
+        use std::path::PathBuf;
+
+        // 原始字节不需要是 UTF-8
+        let bytes: ByteInterner = ByteInterner::with_capacity_and_shards(16, 2);
+        let token: &[u8] = &[0xff, 0x00, 0xfe];
+        let sym = bytes.intern(token);
+        assert_eq!(bytes.intern(&[0xff, 0x00, 0xfe][..]), sym);
+        assert_eq!(bytes.resolve(sym), Some(token));
+        assert_eq!(bytes.get(b"missing"), None);
+        let mut out = Vec::new();
+        bytes.intern_many([&b"a"[..], token, b"a"], &mut out);
+        assert_eq!(out[1], sym);
+        assert_eq!(out[0], out[2]);
+        assert_eq!(bytes.iter().map(|(_, b)| b.len()).sum::<usize>(), 4);
+
+        let os: OsStrInterner = OsStrInterner::default();
+        let arg = os.intern(OsStr::new("--verbose"));
+        assert_eq!(os.resolve(arg), Some(OsStr::new("--verbose")));
+        assert!(os.contains(OsStr::new("--verbose")));
+
+        // 路径按字节驻留，不做规范化
+        let paths: PathInterner = PathInterner::default();
+        let main = paths.intern(Path::new("src/main.rs"));
+        let owned = PathBuf::from("src").join("main.rs");
+        assert_eq!(paths.intern(&owned), main);
+        assert_ne!(paths.intern(Path::new("src//main.rs")), main);
+        assert_eq!(paths.resolve(main), Some(Path::new("src/main.rs")));
+        let static_sym = paths.intern_static(Path::new("Cargo.toml"));
+        assert_eq!(paths.get(Path::new("Cargo.toml")), Some(static_sym));
+        assert_eq!(paths.len(), 3);
+
+        // 同一个 ByteInterner 中，字节串、OsStr 和路径共享 Arena 和编号
+        let shared: ByteInterner = ByteInterner::default();
+        let from_bytes = shared.intern_bytes(b"target");
+        let from_os_str = shared.intern_os_str(OsStr::new("target"));
+        let from_path = shared.intern_path(Path::new("target"));
+        assert_eq!(from_bytes, from_os_str);
+        assert_eq!(from_bytes, from_path);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared.resolve_bytes(from_path), Some(&b"target"[..]));
+        assert_eq!(
+            shared.resolve_os_str(from_bytes),
+            Some(OsStr::new("target"))
+        );
+        assert_eq!(shared.resolve_path(from_bytes), Some(Path::new("target")));
+        let other = shared.intern_path(Path::new("Cargo.lock"));
+        assert_eq!(other.as_u32(), 1);
+        assert_eq!(shared.resolve_path(other), Some(Path::new("Cargo.lock")));
+    }
+
+    #[test]
+    fn test_slice_interner() {
+        // 类型签名：参数和返回值的类型编号
+        let signatures: SliceInterner<u32> = SliceInterner::with_capacity_and_shards(8, 2);
+        let unary = signatures.intern(&[1, 1]);
+        let binary = signatures.intern(&[1, 1, 1]);
+        let owned: Vec<u32> = vec![1, 1];
+        assert_eq!(signatures.intern(&owned), unary);
+        assert_ne!(unary, binary);
+        assert_eq!(signatures.resolve(binary), Some(&[1, 1, 1][..]));
+        let empty = signatures.intern(&[]);
+        assert_eq!(signatures.resolve(empty), Some(&[][..]));
+
+        // 元素按自己的对齐方式存放在 Arena 中
+        let wide: SliceInterner<u64> = SliceInterner::default();
+        wide.intern(&[7]);
+        let sym = wide.intern(&[u64::MAX, 0]);
+        let resolved = wide.resolve(sym).unwrap();
+        assert_eq!(resolved, [u64::MAX, 0]);
+        assert_eq!(resolved.as_ptr() as usize % std::mem::align_of::<u64>(), 0);
+
+        // 由 Symbol 组成的路径，比如 std::collections::HashMap
+        let names: Interner = Interner::new();
+        let path: Vec<Symbol> = ["std", "collections", "HashMap"]
+            .iter()
+            .map(|s| names.intern(s))
+            .collect();
+        let paths: SliceInterner<Symbol> = SliceInterner::default();
+        let sym = paths.intern(&path);
+        let listed: Vec<(Symbol, &[Symbol])> = paths.iter().collect();
+        assert_eq!(listed, [(sym, &path[..])]);
+    }
+
+    #[test]
+    fn test_try_intern() {
+        let interner: Interner = Interner::with_capacity(4);
+        let sym = interner.try_intern("ok").unwrap();
+        assert_eq!(interner.try_intern("ok"), Ok(sym));
+        assert_eq!(interner.resolve(sym), Some("ok"));
+
+        // 错误类型可以被当作标准错误使用
+        let err: Box<dyn std::error::Error> = Box::new(InternError::Alloc(AllocErr));
+        assert!(err.source().is_some());
+        assert_eq!(
+            InternError::SymbolOverflow.to_string(),
+            "symbol id space exhausted"
+        );
+    }
+
+    #[test]
+    fn test_allocation_limit() {
+        let interner: Interner = Interner::new();
+        assert_eq!(interner.allocation_limit(), None);
+        interner.set_allocation_limit(Some(64));
+        assert_eq!(interner.allocation_limit(), Some(64));
+
+        // 上限很小，第一个 chunk 按上限裁剪后仍然可以容纳短字符串
+        let sym = interner.try_intern("short").unwrap();
+        assert!(interner.memory_usage() <= 64);
+
+        // 超出上限之后优雅地失败，并且不会修改池中的内容
+        let long = "x".repeat(1024);
+        assert_eq!(
+            interner.try_intern(&long),
+            Err(InternError::AllocationLimitExceeded)
+        );
+        assert_eq!(interner.len(), 1);
+        assert!(!interner.contains(&long));
+        assert_eq!(interner.resolve(sym), Some("short"));
+        // 已有的字符串不需要分配，所以依然可以驻留
+        assert_eq!(interner.try_intern("short"), Ok(sym));
+
+        // 取消上限之后恢复正常
+        interner.set_allocation_limit(None);
+        let long_sym = interner.try_intern(&long).unwrap();
+        assert_eq!(interner.resolve(long_sym), Some(long.as_str()));
+    }
+
+    #[test]
+    fn test_clear_reuses_arena() {
+        let mut interner: Interner = Interner::with_capacity_and_shards(16, 4);
+        interner.set_allocation_limit(Some(1 << 20));
+        for i in 0..1000 {
+            interner.intern(&format!("before_{i}"));
+        }
+        let usage = interner.memory_usage();
+
+        interner.clear();
+        assert!(interner.is_empty());
+        assert_eq!(interner.iter().count(), 0);
+        assert!(!interner.contains("before_0"));
+        assert!(interner.memory_usage() <= usage);
+        // 分片数量和分配上限保持不变
+        assert_eq!(interner.shard_count(), 4);
+        assert_eq!(interner.allocation_limit(), Some(1 << 20));
+
+        // 清空之后 Symbol 重新从 0 开始编号
+        let sym = interner.intern("after");
+        assert_eq!(sym.as_u32(), 0);
+        assert_eq!(interner.resolve(sym), Some("after"));
+        assert_eq!(interner.get("after"), Some(sym));
+    }
+
+    #[test]
+    fn test_arena_stats() {
+        let interner: Interner = Interner::new();
+        let words = ["alpha", "beta", "gamma", "alpha"];
+        for word in words {
+            interner.intern(word);
+        }
+        // 重复的字符串不会再次分配；字节串不需要对齐填充
+        let stats = interner.arena_stats();
+        assert_eq!(stats.used_bytes, "alphabetagamma".len());
+        assert_eq!(
+            stats.padding_bytes,
+            cfg!(feature = "padding-stats").then_some(0)
+        );
+        assert_eq!(stats.capacity_bytes, interner.memory_usage());
+    }
+
+    #[test]
+    #[should_panic(expected = "allocation limit exceeded")]
+    fn test_intern_panics_over_limit() {
+        let interner: Interner = Interner::new();
+        interner.set_allocation_limit(Some(0));
+        interner.intern("anything");
+    }
+
+    crate::symbols! {
+        /// 测试用的关键字
+        mod kw {
+            Fn: "fn",
+            Let: "let",
+            /// 空字符串同样可以被预定义
+            Empty: "",
+        }
+    }
+
+    #[test]
+    fn test_predefined_symbols() {
+        // 常量的编号在编译期就已经确定
+        const FN: Symbol = kw::Fn;
+        assert_eq!(FN.as_u32(), 0);
+        assert_eq!(kw::Let.as_u32(), 1);
+        assert_eq!(kw::Empty.as_u32(), 2);
+        assert_eq!(kw::STRINGS, ["fn", "let", ""]);
+
+        let interner: Interner = Interner::with_predefined(kw::STRINGS);
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.intern("fn"), kw::Fn);
+        assert_eq!(interner.get("let"), Some(kw::Let));
+        assert_eq!(interner.resolve(kw::Empty), Some(""));
+
+        // 预定义的字符串直接指向 'static 数据，没有被复制进 Arena
+        assert_eq!(
+            interner.resolve(kw::Fn).unwrap().as_ptr(),
+            kw::STRINGS[0].as_ptr()
+        );
+        // Arena 也不会为它们预先申请内存
+        assert_eq!(interner.memory_usage(), 0);
+        assert_eq!(interner.arena_stats().chunk_count, 0);
+
+        // 之后驻留的字符串从预定义的字符串之后开始编号
+        let ident = interner.intern("ident");
+        assert_eq!(ident.as_u32(), 3);
+        assert_ne!(ident, kw::Fn);
+    }
+
+    #[test]
+    fn test_clear_keeps_predefined_symbols() {
+        let mut interner: Interner = Interner::with_predefined(kw::STRINGS);
+        interner.intern("before");
+        interner.intern_static("static_before");
+        interner.clear();
+
+        // 预定义的字符串保留原来的编号，常量在清空之后依然有效
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.resolve(kw::Fn), Some("fn"));
+        assert_eq!(interner.get("let"), Some(kw::Let));
+        assert_eq!(interner.intern(""), kw::Empty);
+        assert!(!interner.contains("before"));
+        assert!(!interner.contains("static_before"));
+
+        // 新的字符串紧接着预定义的前缀编号，不会占用常量的编号
+        let after = interner.intern("after");
+        assert_eq!(after.as_u32(), 3);
+        assert_eq!(interner.resolve(kw::Fn), Some("fn"));
+
+        // 再次清空同样保留它们
+        interner.clear();
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.resolve(after), None);
+    }
+
+    crate::symbols! {
+        mod no_symbols {}
+    }
+
+    crate::symbols! {
+        mod one_symbol {
+            Only: "only"
+        }
+    }
+
+    #[test]
+    fn test_empty_and_single_symbol_modules() {
+        assert!(no_symbols::STRINGS.is_empty());
+        let interner: Interner = Interner::with_predefined(no_symbols::STRINGS);
+        assert!(interner.is_empty());
+
+        assert_eq!(one_symbol::Only.as_u32(), 0);
+        let interner: Interner = Interner::with_predefined(one_symbol::STRINGS);
+        assert_eq!(interner.resolve(one_symbol::Only), Some("only"));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate predefined symbol")]
+    fn test_duplicate_predefined_symbols() {
+        let _: Interner = Interner::with_predefined(&["fn", "let", "fn"]);
+    }
+
+    #[test]
+    fn test_intern_static() {
+        static SOURCE: &str = "fn main() {}";
+
+        let interner: Interner = Interner::new();
+        let sym = interner.intern_static(&SOURCE[..2]);
+        let literal = interner.intern_static("literal");
+
+        // 静态字符串不会进入 Arena
+        assert_eq!(interner.memory_usage(), 0);
+        assert_eq!(interner.resolve(sym).unwrap().as_ptr(), SOURCE.as_ptr());
+        assert_eq!(interner.resolve(literal), Some("literal"));
+
+        // 与普通的 intern 共享同一个符号空间
+        assert_eq!(interner.intern("fn"), sym);
+        assert_eq!(interner.intern_static("literal"), literal);
+        assert_eq!(interner.len(), 2);
+
+        // 已经动态驻留的字符串不会被替换
+        let dynamic = interner.intern("dynamic");
+        assert!(interner.memory_usage() > 0);
+        assert_eq!(interner.intern_static("dynamic"), dynamic);
+        assert_ne!(
+            interner.resolve(dynamic).unwrap().as_ptr(),
+            "dynamic".as_ptr()
+        );
+    }
+
+    #[test]
+    fn test_iter_in_symbol_order() {
+        let interner: Interner = Interner::with_capacity_and_shards(16, 4);
+        let words = ["c", "a", "b", ""];
+        for word in words {
+            interner.intern(word);
+        }
+
+        let listed: Vec<(Symbol, &str)> = interner.iter().collect();
+        assert_eq!(listed.len(), 4);
+        for (i, (sym, s)) in listed.iter().enumerate() {
+            assert_eq!(sym.index(), i);
+            assert_eq!(*s, words[i]);
+        }
+        // &Interner 也可以直接用于 for 循环
+        assert_eq!((&interner).into_iter().len(), 4);
+    }
+
+    #[test]
+    fn test_iter_snapshot_while_interning() {
+        use std::thread;
+
+        let interner: Interner = Interner::with_capacity_and_shards(64, 4);
+        for i in 0..100 {
+            interner.intern(&format!("before{i}"));
+        }
+
+        thread::scope(|s| {
+            // 其他线程不断驻留新的字符串
+            let writer = s.spawn(|| {
+                for i in 0..2000 {
+                    interner.intern(&format!("during{i}"));
+                }
+            });
+
+            // 遍历的长度在创建迭代器时就已经确定，并且编号连续
+            while !writer.is_finished() {
+                let iter = interner.iter();
+                let len = iter.len();
+                assert!(len >= 100);
+                let mut count = 0;
+                for (i, (sym, string)) in iter.enumerate() {
+                    assert_eq!(sym.index(), i);
+                    assert_eq!(interner.resolve(sym), Some(string));
+                    count += 1;
+                }
+                assert_eq!(count, len);
+            }
+        });
+        assert_eq!(interner.iter().count(), 2100);
+    }
+
+    #[test]
+    fn test_get_does_not_intern() {
+        static INTERNER: Lazy<Interner> = Lazy::new(|| Interner::with_capacity(16));
+
+        // 未驻留的字符串查不到，并且不会被插入
+        let usage_before = INTERNER.memory_usage();
+        assert_eq!(INTERNER.get("typo"), None);
+        assert!(!INTERNER.contains("typo"));
+        assert_eq!(INTERNER.len(), 0);
+        assert_eq!(INTERNER.memory_usage(), usage_before);
+
+        // 驻留之后，get 返回同一个 Symbol
+        let sym = INTERNER.intern("ident");
+        assert_eq!(INTERNER.get("ident"), Some(sym));
+        assert!(INTERNER.contains("ident"));
+        assert_eq!(INTERNER.len(), 1);
+    }
+
+    #[test]
+    fn test_owned_interner_on_stack_and_in_arc() {
+        use std::sync::Arc;
+        use std::thread;
+
+        // 栈上的 Interner：resolve 返回的引用绑定在 &interner 上
+        let interner = Interner::new();
+        let sym = interner.intern("session");
+        assert_eq!(interner.resolve(sym), Some("session"));
+        drop(interner);
+
+        // Arc 中的 Interner：可以在多个线程之间共享，最后一个引用释放时整体回收
+        let shared: Arc<Interner> = Arc::new(Interner::with_capacity(8));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || shared.intern("shared"))
+            })
+            .collect();
+        let symbols: Vec<Symbol> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(symbols.windows(2).all(|w| w[0] == w[1]));
+        assert_eq!(shared.resolve(symbols[0]), Some("shared"));
+        assert_eq!(shared.len(), 1);
+    }
+
+    #[test]
+    fn test_intern_many() {
+        let interner: Interner = Interner::with_capacity(16);
+        let existing = interner.intern("fn");
+
+        let mut out = vec![existing];
+        interner.intern_many(["let", "fn", "x", "let", ""], &mut out);
+
+        // 结果被追加在 out 末尾，并且与逐个 intern 的结果一致
+        assert_eq!(out.len(), 6);
+        assert_eq!(out[0], existing);
+        assert_eq!(out[2], existing);
+        assert_eq!(out[1], out[4]);
+        assert_eq!(interner.len(), 4);
+        for (sym, s) in out[1..].iter().zip(["let", "fn", "x", "let", ""]) {
+            assert_eq!(interner.resolve(*sym), Some(s));
+            assert_eq!(interner.intern(s), *sym);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_intern_many() {
+        use std::thread;
+
+        let interner: Interner = Interner::with_capacity(64);
+        let words: Vec<String> = (0..200).map(|i| format!("ident_{}", i % 50)).collect();
+
+        let results: Vec<Vec<Symbol>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut out = Vec::new();
+                        interner.intern_many(words.iter().map(String::as_str), &mut out);
+                        out
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // 所有线程得到的 Symbol 序列完全相同，且每个独立字符串只被驻留一次
+        assert!(results.windows(2).all(|w| w[0] == w[1]));
+        assert_eq!(interner.len(), 50);
+        for (sym, word) in results[0].iter().zip(&words) {
+            assert_eq!(interner.resolve(*sym), Some(word.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_sharded_interning() {
+        use std::collections::HashSet;
+        use std::thread;
+
+        // 分片数量会被向上取整到 2 的幂
+        let interner: Interner = Interner::with_capacity_and_shards(256, 6);
+        assert_eq!(interner.shard_count(), 8);
+        assert_eq!(
+            Interner::<Symbol, 1>::with_capacity_and_shards(0, 0).shard_count(),
+            1
+        );
+
+        let words: Vec<String> = (0..1000).map(|i| format!("word{}", i % 300)).collect();
+        thread::scope(|s| {
+            for t in 0..8 {
+                let words = &words;
+                let interner = &interner;
+                s.spawn(move || {
+                    // 每个线程从不同的位置开始，制造尽可能多的并发未命中
+                    for i in 0..words.len() {
+                        interner.intern(&words[(i + t * 97) % words.len()]);
+                    }
+                });
+            }
+        });
+
+        // 不同分片上的编号仍然是全局唯一且连续的
+        assert_eq!(interner.len(), 300);
+        let ids: HashSet<usize> = (0..300)
+            .map(|i| interner.get(&format!("word{i}")).unwrap().index())
+            .collect();
+        assert_eq!(ids, (0..300).collect());
+        for i in 0..300 {
+            let word = format!("word{i}");
+            assert_eq!(
+                interner.resolve(interner.intern(&word)),
+                Some(word.as_str())
+            );
+        }
+
+        // 批量接口跨分片时同样正确
+        let mut out = Vec::new();
+        interner.intern_many(words.iter().map(String::as_str), &mut out);
+        for (sym, word) in out.iter().zip(&words) {
+            assert_eq!(interner.resolve(*sym), Some(word.as_str()));
+        }
+        assert_eq!(interner.len(), 300);
+    }
+
+    #[test]
+    fn test_resolve_does_not_block_behind_writers() {
+        let interner: Interner = Interner::with_capacity(16);
+        let sym = interner.intern("pretty");
+
+        // 持有所有分片的写锁，模拟正在插入的写入方；resolve 依然可以立刻返回
+        let _guards: Vec<_> = interner.shards.iter().map(|s| s.write().unwrap()).collect();
+        assert_eq!(interner.resolve(sym), Some("pretty"));
+        assert_eq!(interner.len(), 1);
+    }
+
     #[test]
     fn test_concurrent_interning() {
         use std::thread;
 
         // 使用 static Lazy 确保所有线程都访问同一个 Interner 实例
-        static INTERNER: Lazy<Interner<'static>> = Lazy::new(|| Interner::with_capacity(100));
+        static INTERNER: Lazy<Interner> = Lazy::new(|| Interner::with_capacity(100));
 
         // 一些待测试的字符串，包含很多重复项
         let strings_to_intern = vec![
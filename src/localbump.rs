@@ -0,0 +1,353 @@
+// src/localbump.rs
+
+//! 線程私有的分配緩存。
+//!
+//! [`SyncBump`] 的快速路徑每次分配都要對當前 chunk 的 `top` 做一次 CAS。
+//! 當幾十個線程同時分配小對象時，這個指針所在的緩存行會在各個核心之間來回彈跳，
+//! CAS 也會頻繁失敗重試。
+//!
+//! [`LocalBump`] 是一個只屬於單個線程的句柄：它用一次 CAS 從共享的 chunk 中預留一整個子塊，
+//! 之後的分配只在這個子塊中移動一個普通的指針，不涉及任何原子操作；
+//! 子塊用完時，再用一次 CAS 預留下一個。
+
+use crate::syncbump::{AllocErr, SyncBump, oom};
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::fmt;
+use std::ptr::{self, NonNull};
+use std::slice;
+
+/// 默認的子塊大小（字節）。
+///
+/// 對於幾十字節的字符串，一次預留大約可以服務幾十次分配；
+/// 同時它又足夠小，線程退出時被浪費的尾部也不會太多。
+pub const DEFAULT_LOCAL_BLOCK_SIZE: usize = 1 << 10;
+
+/// 子塊本身的對齊。子塊大小也會被向上取整到它的倍數，
+/// 所以子塊的上端總是滿足 `MIN_ALIGN`（`MIN_ALIGN` 不大於 16）。
+const BLOCK_ALIGN: usize = 16;
+
+/// [`SyncBump`] 的線程私有分配句柄，由 [`SyncBump::local`] 創建。
+///
+/// 每個線程各自持有一個 `LocalBump`：它不是 `Sync` 的，分配時只需要 `&self`，
+/// 但不能被多個線程同時使用。分配出來的內存屬於背後的 SyncBump，
+/// 所以返回的引用的生命週期是 `'a`，在句柄被丟棄之後仍然有效。
+///
+/// - 比子塊的四分之一還大的分配會直接走 SyncBump 的共享路徑，不會佔用子塊。
+/// - 子塊剩餘的空間放不下一次分配時，剩下的部分會被放棄，然後預留一個新的子塊。
+///   如果在這期間沒有其他線程從共享 chunk 中分配過，放棄的部分會被原地還給 chunk。
+/// - 句柄被丟棄時，同樣會嘗試把當前子塊中未使用的部分還給 chunk。
+///
+/// ```
+/// use interb::SyncBump;
+/// use std::thread;
+///
+/// let arena = SyncBump::new();
+/// let names: Vec<&str> = thread::scope(|s| {
+///     let handles: Vec<_> = (0..4)
+///         .map(|i| {
+///             let arena = &arena;
+///             s.spawn(move || {
+///                 let local = arena.local();
+///                 let name: &str = local.alloc_str(&format!("worker-{i}"));
+///                 name
+///             })
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect()
+/// });
+/// assert_eq!(names, ["worker-0", "worker-1", "worker-2", "worker-3"]);
+/// ```
+pub struct LocalBump<'a, const MIN_ALIGN: usize = 1> {
+    bump: &'a SyncBump<MIN_ALIGN>,
+    /// 當前子塊的下界。還沒有預留子塊時是空指針。
+    start: Cell<*mut u8>,
+    /// 當前子塊中已經分配出去的部分的下界，與 SyncBump 一樣向下移動。
+    top: Cell<*mut u8>,
+    /// 每次預留的子塊大小。
+    block_size: usize,
+}
+
+// 安全性：子塊是從 SyncBump 中獨占預留的，其他線程不會訪問它，
+// 所以把整個句柄移動到另一個線程是安全的；`Cell` 保證了它不是 `Sync` 的。
+unsafe impl<const MIN_ALIGN: usize> Send for LocalBump<'_, MIN_ALIGN> {}
+
+impl<const MIN_ALIGN: usize> fmt::Debug for LocalBump<'_, MIN_ALIGN> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalBump")
+            .field("block_size", &self.block_size)
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
+    /// 創建一個線程私有的分配句柄，子塊大小為 [`DEFAULT_LOCAL_BLOCK_SIZE`]。
+    ///
+    /// 在每個線程中各創建一個，可以消除多個線程同時分配時對共享 `top` 指針的爭用。
+    pub fn local(&self) -> LocalBump<'_, MIN_ALIGN> {
+        self.local_with_block_size(DEFAULT_LOCAL_BLOCK_SIZE)
+    }
+
+    /// 創建一個線程私有的分配句柄，每次從共享 chunk 中預留 `block_size` 字節。
+    ///
+    /// 子塊越大，需要的 CAS 越少，但每個線程可能浪費的尾部空間也越多。
+    ///
+    /// # Panics
+    /// `block_size` 為 0 時會 panic。
+    pub fn local_with_block_size(&self, block_size: usize) -> LocalBump<'_, MIN_ALIGN> {
+        assert!(block_size > 0, "block size must be non-zero");
+        let block_size = block_size
+            .checked_next_multiple_of(BLOCK_ALIGN)
+            .expect("block size overflowed");
+        LocalBump {
+            bump: self,
+            start: Cell::new(ptr::null_mut()),
+            top: Cell::new(ptr::null_mut()),
+            block_size,
+        }
+    }
+}
+
+impl<'a, const MIN_ALIGN: usize> LocalBump<'a, MIN_ALIGN> {
+    /// 返回背後的 SyncBump。
+    pub fn arena(&self) -> &'a SyncBump<MIN_ALIGN> {
+        self.bump
+    }
+
+    /// 返回每次預留的子塊大小（字節）。
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// 返回當前子塊中還沒有被使用的字節數。
+    pub fn remaining(&self) -> usize {
+        self.top.get() as usize - self.start.get() as usize
+    }
+
+    /// 把 `val` 移動到 Arena 中，返回它的可變引用。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline]
+    pub fn alloc<T>(&self, val: T) -> &'a mut T {
+        self.try_alloc(val).unwrap_or_else(|_| oom())
+    }
+
+    /// [`LocalBump::alloc`] 的可失敗版本。
+    #[inline]
+    pub fn try_alloc<T>(&self, val: T) -> Result<&'a mut T, AllocErr> {
+        let ptr = self.try_alloc_layout(Layout::new::<T>())?.cast::<T>();
+        unsafe {
+            ptr.as_ptr().write(val);
+            Ok(&mut *ptr.as_ptr())
+        }
+    }
+
+    /// 把 `src` 複製到 Arena 中，返回新的字符串切片。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline]
+    pub fn alloc_str(&self, src: &str) -> &'a mut str {
+        self.try_alloc_str(src).unwrap_or_else(|_| oom())
+    }
+
+    /// [`LocalBump::alloc_str`] 的可失敗版本。
+    #[inline]
+    pub fn try_alloc_str(&self, src: &str) -> Result<&'a mut str, AllocErr> {
+        let buffer = self.try_alloc_slice_copy(src.as_bytes())?;
+        // 輸入本來就是 str，所以一定是合法的 UTF-8
+        unsafe { Ok(str::from_utf8_unchecked_mut(buffer)) }
+    }
+
+    /// 把 `src` 按位複製到 Arena 中，返回新的切片。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline]
+    pub fn alloc_slice_copy<T: Copy>(&self, src: &[T]) -> &'a mut [T] {
+        self.try_alloc_slice_copy(src).unwrap_or_else(|_| oom())
+    }
+
+    /// [`LocalBump::alloc_slice_copy`] 的可失敗版本。
+    #[inline]
+    pub fn try_alloc_slice_copy<T: Copy>(&self, src: &[T]) -> Result<&'a mut [T], AllocErr> {
+        let layout = Layout::for_value(src);
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), src.len());
+            Ok(slice::from_raw_parts_mut(dst.as_ptr(), src.len()))
+        }
+    }
+
+    /// 按 `layout` 分配一塊未初始化的內存。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline]
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        self.try_alloc_layout(layout).unwrap_or_else(|_| oom())
+    }
+
+    /// [`LocalBump::alloc_layout`] 的可失敗版本。
+    #[inline]
+    pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        match self.try_alloc_layout_local(layout) {
+            Some(ptr) => Ok(ptr),
+            None => self.alloc_layout_refill(layout),
+        }
+    }
+
+    /// 在當前子塊中分配，不涉及任何原子操作。放不下時返回 None。
+    #[inline(always)]
+    fn try_alloc_layout_local(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let start = self.start.get();
+        let top = self.top.get();
+        // 還沒有預留子塊
+        if start.is_null() {
+            return None;
+        }
+        if layout.size() > top as usize - start as usize {
+            return None;
+        }
+        let align = layout.align().max(MIN_ALIGN);
+        let unaligned = top.wrapping_sub(layout.size());
+        let ptr = unaligned.wrapping_sub(unaligned as usize & (align - 1));
+        if ptr < start {
+            return None;
+        }
+        self.top.set(ptr);
+        Some(unsafe { NonNull::new_unchecked(ptr) })
+    }
+
+    /// 當前子塊放不下時的慢速路徑：大的分配直接交給 SyncBump，
+    /// 小的分配則放棄當前子塊的剩餘部分，預留一個新的子塊。
+    #[inline(never)]
+    #[cold]
+    fn alloc_layout_refill(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        if layout.size() > self.block_size / 4 || layout.align() > BLOCK_ALIGN {
+            return self.bump.try_alloc_layout(layout);
+        }
+
+        self.release_block();
+        let block_layout = Layout::from_size_align(self.block_size, BLOCK_ALIGN).unwrap();
+        match self.bump.try_alloc_layout(block_layout) {
+            Ok(block) => {
+                self.start.set(block.as_ptr());
+                self.top.set(block.as_ptr().wrapping_add(self.block_size));
+                Ok(self
+                    .try_alloc_layout_local(layout)
+                    .expect("BUG: a fresh block must fit a small allocation"))
+            }
+            // 整個子塊超出了分配上限時，這一次分配本身也許還放得下
+            Err(_) => self.bump.try_alloc_layout(layout),
+        }
+    }
+
+    /// 放棄當前子塊。如果它仍然是共享 chunk 中最近的一次分配，就把未使用的部分還回去。
+    fn release_block(&self) {
+        let start = self.start.replace(ptr::null_mut());
+        let top = self.top.replace(ptr::null_mut());
+        if !start.is_null() && start != top {
+            self.bump.try_move_top(start, top);
+        }
+    }
+}
+
+impl<const MIN_ALIGN: usize> Drop for LocalBump<'_, MIN_ALIGN> {
+    fn drop(&mut self) {
+        self.release_block();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_local_alloc_within_one_block() {
+        let arena = SyncBump::new();
+        let local = arena.local_with_block_size(256);
+        let a = local.alloc(1u64);
+        let b = local.alloc_str("hello");
+        let c = local.alloc_slice_copy(&[1u16, 2, 3]);
+        assert_eq!((*a, &*b, &*c), (1, "hello", &[1u16, 2, 3][..]));
+
+        // 三次分配都落在同一個子塊中：子塊只被預留了一次
+        assert!(local.remaining() < 256 - 8 - 5 - 6);
+        assert!(local.remaining() >= 256 - 64);
+    }
+
+    #[test]
+    fn test_local_refill_and_large_allocations() {
+        let arena = SyncBump::new();
+        let local = arena.local_with_block_size(64);
+
+        // 超過子塊四分之一的分配直接走共享路徑，不影響當前子塊
+        let small = local.alloc(7u32);
+        let remaining = local.remaining();
+        let large = local.alloc_slice_copy(&[9u8; 100]);
+        assert_eq!(local.remaining(), remaining);
+
+        // 反覆分配直到觸發多次換塊，之前的分配都保持不變
+        let values: Vec<&mut u64> = (0..100).map(|i| local.alloc(i)).collect();
+        assert!(values.iter().enumerate().all(|(i, v)| **v == i as u64));
+        assert_eq!(*small, 7);
+        assert!(large.iter().all(|&b| b == 9));
+    }
+
+    #[test]
+    fn test_drop_returns_unused_tail() {
+        let arena = SyncBump::new();
+        // 先分配一次，讓 SyncBump 擁有一個 chunk
+        arena.alloc(0u8);
+
+        let last = {
+            let local = arena.local_with_block_size(128);
+            local.alloc(1u8) as *mut u8
+        };
+        // 子塊中未使用的部分已經被還回去：下一次分配緊挨在子塊中唯一的那個字節下方
+        let next = arena.alloc(2u8) as *mut u8;
+        assert_eq!(next, last.wrapping_sub(1));
+    }
+
+    #[test]
+    fn test_allocation_limit_is_respected() {
+        let arena: SyncBump = SyncBump::new();
+        arena.set_allocation_limit(Some(0));
+        let local = arena.local();
+        assert_eq!(local.try_alloc(1u8), Err(AllocErr));
+        assert_eq!(local.try_alloc_str("x").map(|s| &*s), Err(AllocErr));
+    }
+
+    #[test]
+    fn test_concurrent_locals() {
+        let arena = SyncBump::new();
+        let all: Vec<Vec<&str>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|t| {
+                    let arena = &arena;
+                    s.spawn(move || {
+                        let local = arena.local_with_block_size(128);
+                        (0..1000)
+                            .map(|i| &*local.alloc_str(&format!("{t}-{i}")))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // 每個線程的分配都完好無損，也沒有和其他線程的分配重疊
+        for (t, strings) in all.iter().enumerate() {
+            assert!(
+                strings
+                    .iter()
+                    .enumerate()
+                    .all(|(i, s)| *s == format!("{t}-{i}"))
+            );
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// src/macros.rs
+
+/// 聲明一組預定義的字符串，並為它們生成編號固定的 `Symbol` 常量。
+///
+/// 宏會生成一個模塊，其中每個條目對應一個 `pub const` [`Symbol`](crate::Symbol)，
+/// 編號按聲明順序從 0 開始；另外還有一個 `STRINGS` 常量，按同樣的順序列出所有字符串。
+/// 把 `STRINGS` 傳給 [`Interner::with_predefined`](crate::Interner::with_predefined)，
+/// 就能保證這些常量在這個 Interner 中解析到對應的字符串。
+///
+/// ```rust
+/// use interb::{Interner, Symbol};
+///
+/// interb::symbols! {
+///     /// 語言的關鍵字
+///     pub mod kw {
+///         Fn: "fn",
+///         Let: "let",
+///     }
+/// }
+///
+/// let interner: Interner = Interner::with_predefined(kw::STRINGS);
+/// let sym: Symbol = interner.intern("fn");
+/// assert_eq!(sym, kw::Fn);
+/// assert!(matches!(sym, kw::Fn)); // 常量也可以用在模式匹配中
+/// assert_eq!(interner.resolve(kw::Let), Some("let"));
+/// ```
+#[macro_export]
+macro_rules! symbols {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident {
+            $(
+                $(#[$item_meta:meta])*
+                $name:ident : $string:literal
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(non_upper_case_globals)]
+        $vis mod $module {
+            /// 借用枚舉的判別值為每個條目計算出它的編號。
+            // 不加 `#[repr(u32)]`：空的模塊會生成一個沒有變體的枚舉，而它不能指定 repr。
+            #[allow(non_camel_case_types, dead_code)]
+            enum __SymbolIndex {
+                $($name,)*
+            }
+
+            $(
+                $(#[$item_meta])*
+                pub const $name: $crate::Symbol =
+                    $crate::Symbol::from_u32(__SymbolIndex::$name as u32);
+            )*
+
+            /// 所有預定義的字符串，按 Symbol 編號排序。
+            pub const STRINGS: &[&str] = &[$($string),*];
+        }
+    };
+}
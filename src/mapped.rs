@@ -0,0 +1,115 @@
+// src/mapped.rs
+
+//! 一個只讀的、直接借用快照字節的 Interner。
+//!
+//! 多進程構建時，可以由一個進程寫出快照，其他進程把同一個文件映射 (mmap) 到內存中，
+//! 再用 [`MappedInterner`] 共享這張符號表：字符串不會被複製到任何 Arena 中。
+
+use crate::frozen::SortedIndex;
+use crate::snapshot::SnapshotParts;
+use crate::{InternError, SnapshotError, Symbol, SymbolRepr};
+
+/// 借用一段快照字節（比如調用者 mmap 的文件）的只讀 Interner。
+///
+/// 快照的格式與 [`Interner::write_snapshot`](crate::Interner::write_snapshot) 寫出的相同，
+/// Symbol 編號也與寫出快照的 Interner 一致。打開時會完整地校驗快照，
+/// 之後 `resolve` 直接從字節中切出字符串，`get` 則在一個按內容排序的下標表上做二分查找：
+/// 除了這張下標表之外，不會為任何字符串分配堆內存。
+///
+/// 與 [`Interner`](crate::Interner) 一樣，`S` 決定 Symbol 的寬度。
+pub struct MappedInterner<'a, S = Symbol> {
+    /// 已經通過校驗的快照。
+    parts: SnapshotParts<'a>,
+    /// 按字符串內容排序的 Symbol 編號。
+    sorted: SortedIndex<S>,
+}
+
+impl<'a, S: SymbolRepr> MappedInterner<'a, S> {
+    /// 校驗 `bytes` 中的快照，並在其上建立一個只讀的 Interner。
+    ///
+    /// 除了 [`Interner::from_snapshot`](crate::Interner::from_snapshot) 會報告的錯誤之外，
+    /// 快照中出現重複的字符串同樣會返回 [`SnapshotError::DuplicateString`]，
+    /// 字符串數量超出 `S` 能表示的範圍則返回 [`InternError::SymbolOverflow`]。
+    pub fn new(bytes: &'a [u8]) -> Result<Self, SnapshotError> {
+        let parts = SnapshotParts::parse(bytes)?;
+        if parts.count > S::MAX_COUNT {
+            return Err(SnapshotError::Intern(InternError::SymbolOverflow));
+        }
+
+        let sorted = SortedIndex::build(parts.count, |i| parts.string(i))
+            .map_err(SnapshotError::DuplicateString)?;
+        Ok(MappedInterner { parts, sorted })
+    }
+
+    /// 根據 Symbol 取回字符串，它直接借用自快照的字節。
+    /// 如果 Symbol 不在這張表中，返回 None。
+    pub fn resolve(&self, symbol: S) -> Option<&'a str> {
+        (symbol.index() < self.parts.count).then(|| self.parts.string(symbol.index()))
+    }
+
+    /// 查找一個字符串的 Symbol；不在表中的字符串返回 None。
+    pub fn get(&self, s: &str) -> Option<S> {
+        self.sorted.find(s, |i| self.parts.string(i))
+    }
+
+    /// 檢查字符串是否在表中。
+    pub fn contains(&self, s: &str) -> bool {
+        self.get(s).is_some()
+    }
+
+    /// 返回表中字符串的數量。
+    pub fn len(&self) -> usize {
+        self.parts.count
+    }
+
+    /// 檢查表是否為空。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interner;
+
+    #[test]
+    fn test_mapped_interner_matches_source() {
+        let interner: Interner = Interner::with_capacity(16);
+        let words = ["zeta", "alpha", "", "数据", "mid"];
+        let symbols: Vec<Symbol> = words.iter().map(|s| interner.intern(s)).collect();
+        let mut bytes = Vec::new();
+        interner.write_snapshot(&mut bytes).unwrap();
+
+        let mapped: MappedInterner = MappedInterner::new(&bytes).unwrap();
+        assert_eq!(mapped.len(), words.len());
+        let range = bytes.as_ptr_range();
+        for (sym, word) in symbols.iter().zip(words) {
+            let resolved = mapped.resolve(*sym).unwrap();
+            assert_eq!(resolved, word);
+            // 字符串直接借用自快照的字节
+            assert!(range.contains(&resolved.as_ptr()) || resolved.is_empty());
+            assert_eq!(mapped.get(word), Some(*sym));
+        }
+
+        // 不存在的字符串和 Symbol 都干净地失败
+        assert_eq!(mapped.get("missing"), None);
+        assert!(!mapped.contains("alph"));
+        assert_eq!(mapped.resolve(Symbol::from_u32(words.len() as u32)), None);
+    }
+
+    #[test]
+    fn test_mapped_interner_rejects_invalid_snapshots() {
+        assert_eq!(
+            MappedInterner::<Symbol>::new(b"garbage").err(),
+            Some(SnapshotError::BadMagic)
+        );
+
+        let empty: Interner = Interner::new();
+        let mut bytes = Vec::new();
+        empty.write_snapshot(&mut bytes).unwrap();
+        let mapped: MappedInterner = MappedInterner::new(&bytes).unwrap();
+        assert!(mapped.is_empty());
+        assert_eq!(mapped.get(""), None);
+    }
+}
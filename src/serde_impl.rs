@@ -0,0 +1,286 @@
+// src/serde_impl.rs
+
+//! `serde` 特性：`Symbol` 與 `Interner` 的序列化和反序列化。
+//!
+//! - `Symbol`（以及 `Symbol16`、`Symbol64`）默認被序列化為它的原始編號，只對同一張字符串表有意義。
+//! - `Interner` 被序列化為一個按 Symbol 編號排列的字符串序列，
+//!   反序列化後得到的 Interner 中每個字符串的編號都與原來相同。
+//! - 如果希望把 Symbol 序列化為它所代表的字符串，可以借助 Interner 作為上下文：
+//!   序列化時使用 [`ResolvedSymbol`]，反序列化時使用 [`InternSeed`]。
+
+use crate::{Interner, Symbol, Symbol16, Symbol64, SymbolRepr};
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// 把一個 Symbol 類型序列化為它的原始編號。
+macro_rules! impl_symbol_serde {
+    ($name:ident, $int:ident, $serialize:ident) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.$serialize(self.index() as $int)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = $int::deserialize(deserializer)?;
+                // 最大值被留給了 niche，不是一個合法的編號。
+                if id == $int::MAX {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(id.into()),
+                        &concat!("a symbol id smaller than ", stringify!($int), "::MAX"),
+                    ));
+                }
+                Ok($name::from_index(id as usize))
+            }
+        }
+    };
+}
+
+impl_symbol_serde!(Symbol, u32, serialize_u32);
+impl_symbol_serde!(Symbol16, u16, serialize_u16);
+impl_symbol_serde!(Symbol64, u64, serialize_u64);
+
+/// 把整個字符串表按 Symbol 編號的順序序列化為一個字符串序列。
+///
+/// 如果其他線程正在並發地駐留，序列化的是調用時已經存在的那些字符串。
+impl<Sym: SymbolRepr, const MIN_ALIGN: usize> Serialize for Interner<Sym, MIN_ALIGN> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let iter = self.iter();
+        let mut seq = serializer.serialize_seq(Some(iter.len()))?;
+        for (_, s) in iter {
+            seq.serialize_element(s)?;
+        }
+        seq.end()
+    }
+}
+
+/// 從一個字符串序列重建 Interner，第 `i` 個字符串的 Symbol 編號就是 `i`。
+///
+/// 序列中不能有重複的字符串，否則編號無法保持一致。
+impl<'de, Sym: SymbolRepr, const MIN_ALIGN: usize> Deserialize<'de> for Interner<Sym, MIN_ALIGN> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(InternerVisitor(PhantomData))
+    }
+}
+
+/// 反序列化 Interner 時最多按多少個字符串預先預留容量。
+const MAX_PREALLOCATED_STRINGS: usize = 4096;
+
+struct InternerVisitor<Sym, const MIN_ALIGN: usize>(PhantomData<Interner<Sym, MIN_ALIGN>>);
+
+impl<'de, Sym: SymbolRepr, const MIN_ALIGN: usize> Visitor<'de>
+    for InternerVisitor<Sym, MIN_ALIGN>
+{
+    type Value = Interner<Sym, MIN_ALIGN>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of unique strings ordered by symbol id")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // 長度來自輸入本身，不可信：只按一個有上限的值預留容量，其餘的隨插入增長。
+        let capacity = seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED_STRINGS);
+        let interner: Interner<Sym, MIN_ALIGN> = Interner::with_capacity(capacity);
+        // 字符串直接從輸入駐留到 Arena 中，不需要先為每個元素分配一個 String。
+        let mut expected = 0;
+        while let Some(symbol) = seq.next_element_seed(InternSeed::new(&interner))? {
+            if symbol.index() != expected {
+                let s = interner.resolve(symbol).unwrap_or_default();
+                return Err(de::Error::custom(format_args!(
+                    "duplicate string {s:?} in interner table at index {expected}"
+                )));
+            }
+            expected += 1;
+        }
+        Ok(interner)
+    }
+}
+
+/// 以 Interner 為上下文，把一個 Symbol 序列化為它所代表的字符串。
+///
+/// ```rust
+/// # #[cfg(feature = "serde")] {
+/// use interb::{Interner, ResolvedSymbol};
+///
+/// let interner: Interner = Interner::new();
+/// let sym = interner.intern("main");
+/// let json = serde_json::to_string(&ResolvedSymbol::new(&interner, sym)).unwrap();
+/// assert_eq!(json, r#""main""#);
+/// # }
+/// ```
+pub struct ResolvedSymbol<'a, Sym = Symbol, const MIN_ALIGN: usize = 1> {
+    interner: &'a Interner<Sym, MIN_ALIGN>,
+    symbol: Sym,
+}
+
+impl<'a, Sym: SymbolRepr, const MIN_ALIGN: usize> ResolvedSymbol<'a, Sym, MIN_ALIGN> {
+    /// 把 `symbol` 與產生它的 `interner` 綁定在一起。
+    pub fn new(interner: &'a Interner<Sym, MIN_ALIGN>, symbol: Sym) -> Self {
+        ResolvedSymbol { interner, symbol }
+    }
+}
+
+/// 如果 Symbol 在這個 Interner 中不存在，序列化會失敗。
+impl<Sym: SymbolRepr, const MIN_ALIGN: usize> Serialize for ResolvedSymbol<'_, Sym, MIN_ALIGN> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.interner.resolve(self.symbol) {
+            Some(s) => serializer.serialize_str(s),
+            None => Err(ser::Error::custom(format_args!(
+                "{:?} does not belong to this interner",
+                self.symbol
+            ))),
+        }
+    }
+}
+
+/// 以 Interner 為上下文，把一個字符串反序列化並駐留為 Symbol。
+///
+/// ```rust
+/// # #[cfg(feature = "serde")] {
+/// use interb::{InternSeed, Interner};
+/// use serde::de::DeserializeSeed;
+///
+/// let interner: Interner = Interner::new();
+/// let mut de = serde_json::Deserializer::from_str(r#""main""#);
+/// let sym = InternSeed::new(&interner).deserialize(&mut de).unwrap();
+/// assert_eq!(interner.resolve(sym), Some("main"));
+/// # }
+/// ```
+pub struct InternSeed<'a, Sym = Symbol, const MIN_ALIGN: usize = 1> {
+    interner: &'a Interner<Sym, MIN_ALIGN>,
+}
+
+impl<'a, Sym: SymbolRepr, const MIN_ALIGN: usize> InternSeed<'a, Sym, MIN_ALIGN> {
+    /// 創建一個把字符串駐留到 `interner` 中的種子。
+    pub fn new(interner: &'a Interner<Sym, MIN_ALIGN>) -> Self {
+        InternSeed { interner }
+    }
+}
+
+impl<Sym, const MIN_ALIGN: usize> Clone for InternSeed<'_, Sym, MIN_ALIGN> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Sym, const MIN_ALIGN: usize> Copy for InternSeed<'_, Sym, MIN_ALIGN> {}
+
+impl<'de, Sym: SymbolRepr, const MIN_ALIGN: usize> DeserializeSeed<'de>
+    for InternSeed<'_, Sym, MIN_ALIGN>
+{
+    type Value = Sym;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Sym, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, Sym: SymbolRepr, const MIN_ALIGN: usize> Visitor<'de> for InternSeed<'_, Sym, MIN_ALIGN> {
+    type Value = Sym;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Sym, E> {
+        self.interner.try_intern(s).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_as_raw_id() {
+        let sym = Symbol::from_u32(42);
+        assert_eq!(serde_json::to_string(&sym).unwrap(), "42");
+        assert_eq!(serde_json::from_str::<Symbol>("42").unwrap(), sym);
+        assert_eq!(
+            serde_json::from_str::<Option<Symbol>>("null").unwrap(),
+            None
+        );
+        assert!(serde_json::from_str::<Symbol>(&u32::MAX.to_string()).is_err());
+
+        // 其他宽度使用各自的整数类型
+        assert_eq!(serde_json::to_string(&Symbol16::from_u16(7)).unwrap(), "7");
+        assert!(serde_json::from_str::<Symbol16>(&u16::MAX.to_string()).is_err());
+        assert_eq!(
+            serde_json::from_str::<Symbol64>("5000000000").unwrap(),
+            Symbol64::from_u64(5_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_interner_round_trip_keeps_ids() {
+        let interner: Interner = Interner::with_capacity_and_shards(16, 4);
+        let symbols: Vec<Symbol> = ["fn", "main", "", "let", "x"]
+            .iter()
+            .map(|s| interner.intern(s))
+            .collect();
+
+        let json = serde_json::to_string(&interner).unwrap();
+        assert_eq!(json, r#"["fn","main","","let","x"]"#);
+
+        let restored: Interner = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.len(), interner.len());
+        for sym in symbols {
+            assert_eq!(restored.resolve(sym), interner.resolve(sym));
+            assert_eq!(restored.get(interner.resolve(sym).unwrap()), Some(sym));
+        }
+    }
+
+    #[test]
+    fn test_interner_rejects_duplicates() {
+        let err = serde_json::from_str::<Interner>(r#"["a","b","a"]"#)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("duplicate string"));
+    }
+
+    #[test]
+    fn test_interner_ignores_forged_length() {
+        use serde::de::value::{Error, SeqDeserializer};
+
+        // 声明了 usize::MAX 个元素、实际只有两个的序列
+        struct Forged(std::vec::IntoIter<&'static str>);
+        impl Iterator for Forged {
+            type Item = &'static str;
+            fn next(&mut self) -> Option<&'static str> {
+                self.0.next()
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, Some(usize::MAX))
+            }
+        }
+
+        let seq = SeqDeserializer::<_, Error>::new(Forged(vec!["a", "b"].into_iter()));
+        let interner = Interner::<Symbol>::deserialize(seq).unwrap();
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(Symbol::from_u32(1)), Some("b"));
+        // 没有按声明的长度预留内存
+        assert!(interner.memory_usage() < 1 << 20);
+    }
+
+    #[test]
+    fn test_symbol_through_interner_context() {
+        let source: Interner = Interner::new();
+        let sym = source.intern("ident");
+
+        let json = serde_json::to_string(&ResolvedSymbol::new(&source, sym)).unwrap();
+        assert_eq!(json, r#""ident""#);
+        assert!(serde_json::to_string(&ResolvedSymbol::new(&source, Symbol::from_u32(7))).is_err());
+
+        // 反序列化到另一个 Interner 中：字符串相同，编号可以不同
+        let target: Interner = Interner::new();
+        target.intern("other");
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let restored = InternSeed::new(&target).deserialize(&mut de).unwrap();
+        assert_eq!(target.resolve(restored), Some("ident"));
+        assert_ne!(restored, sym);
+    }
+}
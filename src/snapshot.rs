@@ -0,0 +1,329 @@
+// src/snapshot.rs
+
+//! 緊湊的二進制快照格式，用於把 Interner 保存到磁盤並快速地重新加載。
+//!
+//! 所有整數都是小端序。一個快照由三部分組成：
+//!
+//! ```text
+//! +---------------------------------------------------------------+
+//! | 頭部 (40 字節)                                                 |
+//! |   magic:    [u8; 8]  = b"INTERB\0\0"                           |
+//! |   version:  u32      = 1                                       |
+//! |   flags:    u32      = 0（保留）                                |
+//! |   count:    u64      字符串的數量                               |
+//! |   blob_len: u64      字符串區的字節數                           |
+//! |   checksum: u64      偏移表和字符串區的 FNV-1a 64 位哈希        |
+//! +---------------------------------------------------------------+
+//! | 偏移表: (count + 1) 個 u64                                     |
+//! |   第 i 個字符串是 blob[offsets[i]..offsets[i + 1]]             |
+//! +---------------------------------------------------------------+
+//! | 字符串區: blob_len 個字節，所有字符串按 Symbol 編號依次拼接      |
+//! +---------------------------------------------------------------+
+//! ```
+//!
+//! 第 `i` 個字符串的 Symbol 編號就是 `i`，所以重新加載後的 Interner 與保存時的編號完全一致。
+
+use crate::syncbump::SyncBump;
+use crate::{Interner, SnapshotError, SymbolRepr};
+use std::io::{self, Write};
+use std::mem::size_of;
+
+/// 快照開頭的魔數。
+const MAGIC: [u8; 8] = *b"INTERB\0\0";
+/// 當前的快照格式版本。
+const VERSION: u32 = 1;
+/// 頭部的字節數。
+const HEADER_LEN: usize = 40;
+/// 偏移表中每一項的字節數。
+const OFFSET_LEN: usize = size_of::<u64>();
+
+/// 一個已經通過了所有校驗的快照的各個組成部分，字符串都借用自原始的字節。
+pub(crate) struct SnapshotParts<'a> {
+    /// 字符串的數量。
+    pub(crate) count: usize,
+    /// 原始的偏移表，共 `count + 1` 項。
+    offsets: &'a [u8],
+    /// 所有字符串拼接而成的字符串區。
+    pub(crate) blob: &'a str,
+}
+
+impl<'a> SnapshotParts<'a> {
+    /// 解析並校驗一個快照：魔數、版本、標誌位、長度、校驗和、偏移表以及 UTF-8 編碼。
+    ///
+    /// 校驗通過之後，`string(i)` 對所有 `i < count` 都不會失敗。
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < HEADER_LEN || bytes[..8] != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = read_u32(bytes, 8);
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        // 標誌位是保留給以後的版本的，不認識的標誌位不能被悄悄忽略。
+        let flags = read_u32(bytes, 12);
+        if flags != 0 {
+            return Err(SnapshotError::UnsupportedFlags(flags));
+        }
+        let count =
+            usize::try_from(read_u64(bytes, 16)).map_err(|_| SnapshotError::LengthMismatch)?;
+        let blob_len =
+            usize::try_from(read_u64(bytes, 24)).map_err(|_| SnapshotError::LengthMismatch)?;
+        let checksum = read_u64(bytes, 32);
+
+        // 用帶檢查的算術計算各部分的範圍，防止惡意的頭部導致溢出。
+        let offsets_len = count
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(OFFSET_LEN))
+            .ok_or(SnapshotError::LengthMismatch)?;
+        let total_len = HEADER_LEN
+            .checked_add(offsets_len)
+            .and_then(|n| n.checked_add(blob_len))
+            .ok_or(SnapshotError::LengthMismatch)?;
+        if bytes.len() != total_len {
+            return Err(SnapshotError::LengthMismatch);
+        }
+
+        let body = &bytes[HEADER_LEN..];
+        if fnv1a(FNV_OFFSET_BASIS, body) != checksum {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+        let (offsets, blob) = body.split_at(offsets_len);
+
+        // 拼接起來的合法 UTF-8 字符串仍然是合法的 UTF-8，
+        // 所以只需要驗證一次整個字符串區，再檢查每個偏移都落在字符邊界上。
+        let blob = str::from_utf8(blob).map_err(|_| SnapshotError::InvalidUtf8)?;
+        let parts = SnapshotParts {
+            count,
+            offsets,
+            blob,
+        };
+        if parts.offset(0) != 0 || parts.offset(count) != blob_len {
+            return Err(SnapshotError::InvalidOffsets);
+        }
+        for index in 0..count {
+            let (start, end) = (parts.offset(index), parts.offset(index + 1));
+            if start > end || end > blob_len {
+                return Err(SnapshotError::InvalidOffsets);
+            }
+            if !blob.is_char_boundary(start) {
+                return Err(SnapshotError::InvalidUtf8);
+            }
+        }
+        Ok(parts)
+    }
+
+    /// 偏移表中的第 `index` 項。超出 usize 的值會被截斷為 `usize::MAX`，從而無法通過邊界檢查。
+    fn offset(&self, index: usize) -> usize {
+        let offset = read_u64(self.offsets, index * OFFSET_LEN);
+        usize::try_from(offset).unwrap_or(usize::MAX)
+    }
+
+    /// 返回第 `index` 個字符串。調用者需要保證 `index < count`。
+    pub(crate) fn string(&self, index: usize) -> &'a str {
+        &self.blob[self.offset(index)..self.offset(index + 1)]
+    }
+}
+
+impl<S: SymbolRepr, const MIN_ALIGN: usize> Interner<S, MIN_ALIGN> {
+    /// 把當前的字符串表寫成一個二進制快照，格式見 [`Interner::from_snapshot`]。
+    ///
+    /// 如果其他線程正在並發地駐留，寫入的是調用時已經存在的那些字符串。
+    pub fn write_snapshot<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let strings: Vec<&str> = self.iter().map(|(_, s)| s).collect();
+        let count = strings.len();
+
+        let mut offsets = Vec::with_capacity((count + 1) * OFFSET_LEN);
+        let mut offset = 0u64;
+        offsets.extend_from_slice(&offset.to_le_bytes());
+        for s in &strings {
+            offset += s.len() as u64;
+            offsets.extend_from_slice(&offset.to_le_bytes());
+        }
+        let blob_len = offset;
+
+        let checksum = strings
+            .iter()
+            .fold(fnv1a(FNV_OFFSET_BASIS, &offsets), |hash, s| {
+                fnv1a(hash, s.as_bytes())
+            });
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..8].copy_from_slice(&MAGIC);
+        header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        header[16..24].copy_from_slice(&(count as u64).to_le_bytes());
+        header[24..32].copy_from_slice(&blob_len.to_le_bytes());
+        header[32..40].copy_from_slice(&checksum.to_le_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&offsets)?;
+        for s in &strings {
+            writer.write_all(s.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// 從 [`Interner::write_snapshot`] 寫出的快照重建 Interner。
+    ///
+    /// 快照的版本、長度、校驗和、偏移表和 UTF-8 編碼都會被校驗；
+    /// 重建後每個字符串的 Symbol 編號都與保存時相同。
+    /// 整個字符串區只會被一次性地複製進新 Interner 的 Arena，不會逐個字符串分配。
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let parts = SnapshotParts::parse(bytes)?;
+        let arena = SyncBump::<MIN_ALIGN>::try_with_capacity(parts.blob.len())
+            .map_err(crate::InternError::Alloc)?;
+        let interner = Self::with_arena(parts.count, 1, arena);
+
+        let blob = interner.alloc_in_arena(parts.blob.as_bytes())?;
+        let base = parts.blob.as_ptr() as usize;
+        for index in 0..parts.count {
+            // 在 Arena 中的副本上取出同樣範圍的子串，它的地址是穩定的。
+            let s = parts.string(index);
+            let start = s.as_ptr() as usize - base;
+            let s: &'static [u8] = &blob[start..start + s.len()];
+
+            let symbol = interner.intern_with(s, || Ok(s))?;
+            if symbol.index() != index {
+                return Err(SnapshotError::DuplicateString(index));
+            }
+        }
+        Ok(interner)
+    }
+}
+
+/// FNV-1a 64 位哈希的初始值。
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a 64 位哈希的質數。
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 以 `hash` 為初始狀態，繼續計算 `bytes` 的 FNV-1a 哈希。
+/// 這樣可以分段地計算一段不連續數據的校驗和。
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+
+    fn snapshot_of(interner: &Interner) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        interner.write_snapshot(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let interner: Interner = Interner::with_capacity_and_shards(16, 4);
+        let words = ["fn", "main", "", "数据", "let", "x"];
+        let symbols: Vec<Symbol> = words.iter().map(|s| interner.intern(s)).collect();
+
+        let bytes = snapshot_of(&interner);
+        assert_eq!(
+            bytes.len(),
+            HEADER_LEN + 7 * OFFSET_LEN + words.concat().len()
+        );
+
+        let restored: Interner = Interner::from_snapshot(&bytes).unwrap();
+        assert_eq!(restored.len(), words.len());
+        for (sym, word) in symbols.iter().zip(words) {
+            assert_eq!(restored.resolve(*sym), Some(word));
+            assert_eq!(restored.get(word), Some(*sym));
+        }
+
+        // 重建之后可以继续驻留，编号接着往后排
+        assert_eq!(restored.intern("new").as_u32(), words.len() as u32);
+        // 字符串区被一次性复制进了 Arena
+        assert!(restored.memory_usage() >= words.concat().len());
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        let interner: Interner = Interner::new();
+        let bytes = snapshot_of(&interner);
+        assert_eq!(bytes.len(), HEADER_LEN + OFFSET_LEN);
+        let restored: Interner = Interner::from_snapshot(&bytes).unwrap();
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_validation() {
+        let interner: Interner = Interner::new();
+        interner.intern("hello");
+        interner.intern("world");
+        let bytes = snapshot_of(&interner);
+
+        let load = |bytes: &[u8]| Interner::<Symbol, 1>::from_snapshot(bytes).err().unwrap();
+
+        assert_eq!(load(b"not a snapshot"), SnapshotError::BadMagic);
+
+        let mut bad_version = bytes.clone();
+        bad_version[8] = 2;
+        assert_eq!(load(&bad_version), SnapshotError::UnsupportedVersion(2));
+
+        // 保留的标志位必须为 0
+        let mut bad_flags = bytes.clone();
+        bad_flags[12] = 1;
+        assert_eq!(load(&bad_flags), SnapshotError::UnsupportedFlags(1));
+        assert!(crate::MappedInterner::<Symbol>::new(&bad_flags).is_err());
+
+        assert_eq!(
+            load(&bytes[..bytes.len() - 1]),
+            SnapshotError::LengthMismatch
+        );
+
+        let mut huge_count = bytes.clone();
+        huge_count[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(load(&huge_count), SnapshotError::LengthMismatch);
+
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        assert_eq!(load(&corrupted), SnapshotError::ChecksumMismatch);
+    }
+
+    #[test]
+    fn test_snapshot_semantic_errors() {
+        // 手工构造校验和正确、但内容非法的快照
+        fn build(offsets: &[u64], blob: &[u8]) -> Vec<u8> {
+            let count = offsets.len() as u64 - 1;
+            let mut body: Vec<u8> = offsets.iter().flat_map(|o| o.to_le_bytes()).collect();
+            body.extend_from_slice(blob);
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend_from_slice(&VERSION.to_le_bytes());
+            bytes.extend_from_slice(&0u32.to_le_bytes());
+            bytes.extend_from_slice(&count.to_le_bytes());
+            bytes.extend_from_slice(&(blob.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&fnv1a(FNV_OFFSET_BASIS, &body).to_le_bytes());
+            bytes.extend_from_slice(&body);
+            bytes
+        }
+        let load = |bytes: &[u8]| Interner::<Symbol, 1>::from_snapshot(bytes).err().unwrap();
+
+        assert!(Interner::<Symbol, 1>::from_snapshot(&build(&[0, 1, 2], b"ab")).is_ok());
+        assert_eq!(
+            load(&build(&[0, 2, 1], b"ab")),
+            SnapshotError::InvalidOffsets
+        );
+        assert_eq!(load(&build(&[1, 2], b"ab")), SnapshotError::InvalidOffsets);
+        assert_eq!(load(&build(&[0, 1], b"\xff")), SnapshotError::InvalidUtf8);
+        // 偏移落在一个多字节字符的中间
+        assert_eq!(
+            load(&build(&[0, 1, 3], "数".as_bytes())),
+            SnapshotError::InvalidUtf8
+        );
+        assert_eq!(
+            load(&build(&[0, 1, 2], b"aa")),
+            SnapshotError::DuplicateString(1)
+        );
+    }
+}
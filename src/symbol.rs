@@ -0,0 +1,121 @@
+// src/symbol.rs
+
+//! Symbol 類型，以及決定 Symbol 寬度的 [`SymbolRepr`] trait。
+//!
+//! 默認的 [`Symbol`] 是 4 個字節。小型 DSL 可以使用 2 字節的 [`Symbol16`] 把 AST 節點排得更緊湊，
+//! 字符串數量可能超過 40 億的場景則可以使用 8 字節的 [`Symbol64`]。
+//! 它們都存儲「編號 + 1」，所以 `Option<SymbolXX>` 與 `SymbolXX` 的大小相同。
+
+use std::fmt;
+use std::hash::Hash;
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64};
+
+/// Symbol 的表示方式，作為 [`Interner`](crate::Interner) 的第一個泛型參數。
+///
+/// 一種表示決定了 Symbol 的大小和一個 Interner 最多能容納多少個獨立字符串：
+/// 編號用完之後，[`Interner::try_intern`](crate::Interner::try_intern) 會返回
+/// [`InternError::SymbolOverflow`](crate::InternError::SymbolOverflow)。
+///
+/// ```
+/// use interb::{Interner, Symbol16};
+///
+/// let interner: Interner<Symbol16> = Interner::default();
+/// let sym = interner.intern("x");
+/// assert_eq!(std::mem::size_of_val(&sym), 2);
+/// assert_eq!(interner.resolve(sym), Some("x"));
+/// assert_eq!(std::mem::size_of::<Option<Symbol16>>(), 2);
+/// ```
+pub trait SymbolRepr: Copy + Eq + Hash + fmt::Debug + Send + Sync + 'static {
+    /// 這種表示能區分的編號數量：編號 `0..MAX_COUNT` 都可以被表示。
+    const MAX_COUNT: usize;
+
+    /// 從 0 開始的編號構造一個 Symbol。
+    /// 調用者保證 `index < Self::MAX_COUNT`。
+    fn from_index(index: usize) -> Self;
+
+    /// 返回從 0 開始的編號，也就是這個 Symbol 在符號表中的下標。
+    fn index(self) -> usize;
+}
+
+/// 為一種整數寬度定義一個 Symbol 類型，並為它實現 [`SymbolRepr`]。
+macro_rules! define_symbol {
+    (
+        $(#[$meta:meta])*
+        $name:ident($nonzero:ty, $int:ty), $from:ident, $as:ident
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name($nonzero);
+
+        impl $name {
+            #[doc = concat!("從 0 開始的編號構造一個 Symbol。\n",
+                "編號 `", stringify!($int), "::MAX` 沒有對應的表示，",
+                "會導致 panic（在 `const` 上下文中則是編譯錯誤）。")]
+            #[inline]
+            pub const fn $from(id: $int) -> Self {
+                match <$nonzero>::new(id.wrapping_add(1)) {
+                    Some(repr) => $name(repr),
+                    None => panic!("symbol id overflowed"),
+                }
+            }
+
+            /// 返回這個 Symbol 從 0 開始的編號。
+            #[inline]
+            pub const fn $as(self) -> $int {
+                self.0.get() - 1
+            }
+
+            /// 返回這個 Symbol 在符號表中的下標。
+            #[inline]
+            pub(crate) const fn index(self) -> usize {
+                self.$as() as usize
+            }
+        }
+
+        /// 打印從 0 開始的編號，而不是內部的「編號 + 1」。
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.index()).finish()
+            }
+        }
+
+        impl SymbolRepr for $name {
+            const MAX_COUNT: usize = <$int>::MAX as usize;
+
+            #[inline]
+            fn from_index(index: usize) -> Self {
+                $name::$from(index as $int)
+            }
+
+            #[inline]
+            fn index(self) -> usize {
+                $name::index(self)
+            }
+        }
+    };
+}
+
+define_symbol! {
+    /// 一個輕量級的、唯一的字符串標識符。
+    /// 它可以被高效地複製、傳遞、比較和用作哈希表的鍵。
+    ///
+    /// 內部存儲的是「編號 + 1」，這樣 0 就成了一個永遠不會出現的值，
+    /// 編譯器可以用它來表示 `None`，讓 `Option<Symbol>` 和 `Symbol` 一樣只佔 4 個字節。
+    ///
+    /// 一個 Symbol 只對產生它的 Interner 有意義；對一個不存在的編號調用
+    /// [`Interner::resolve`](crate::Interner::resolve) 只會返回 `None`。
+    /// [`Symbol::from_u32`] 主要用於 [`symbols!`](crate::symbols) 生成的常量。
+    Symbol(NonZeroU32, u32), from_u32, as_u32
+}
+
+define_symbol! {
+    /// 2 字節的 Symbol，最多可以區分 65535 個字符串。
+    ///
+    /// 適合字符串數量有限的小型 DSL：`Option<Symbol16>` 同樣只佔 2 個字節。
+    Symbol16(NonZeroU16, u16), from_u16, as_u16
+}
+
+define_symbol! {
+    /// 8 字節的 Symbol，用於獨立字符串的數量可能超過 `u32` 範圍的場景。
+    Symbol64(NonZeroU64, u64), from_u64, as_u64
+}
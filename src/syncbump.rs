@@ -11,8 +11,10 @@ use super::chunkfooter::{ChunkFooter, EMPTY_CHUNK};
 use core::cmp::Ordering as MemOrdering;
 use std::alloc::{Layout, alloc};
 use std::fmt;
-use std::iter;
+use std::iter::{self, FusedIterator};
+use std::marker::PhantomData;
 use std::mem;
+use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 use std::slice;
 use std::sync::Mutex;
@@ -63,6 +65,55 @@ const DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER: usize = FIRST_ALLOCATION_GOAL - OVERHEA
 /// 線程安全的 Bump Allocator
 /// 編譯器可以自動推斷出這個結構體是Sync的，
 /// 同時就會自動滿足Send的條件
+///
+/// # 線程安全
+///
+/// SyncBump 是 `Send + Sync` 的，所有分配方法都只需要 `&self`，
+/// 所以同一個 SyncBump 可以被任意多個線程同時用來分配：
+///
+/// - 在當前 chunk 中分配是無鎖的：每個線程用一次 CAS 把 `top` 指針向下移動，
+///   衝突時只會重試，不會阻塞。
+/// - 當前 chunk 用完時，只有一個線程會拿到慢速路徑的鎖去申請新的 chunk，
+///   其他同時用完空間的線程會等待它，然後直接在新的 chunk 中分配。
+/// - 每次分配返回的內存互不重疊，返回的 `&mut T` 借用自 `&self`，
+///   在 SyncBump 被析構之前一直有效，地址也不會移動。
+///
+/// 與 `bumpalo` 一樣，SyncBump **不會**調用分配在其中的值的析構函數：
+/// 內存只會在 SyncBump 本身被析構時整體歸還給全局分配器。
+/// 需要析構的類型（比如持有 `Vec` 或 `String` 的節點）放進來時，它們的堆內存會被洩漏。
+///
+/// ```
+/// use interb::SyncBump;
+/// use std::thread;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Expr<'a> {
+///     Num(i64),
+///     Add(&'a Expr<'a>, &'a Expr<'a>),
+/// }
+///
+/// let arena = SyncBump::new();
+/// let sums: Vec<&Expr> = thread::scope(|s| {
+///     let handles: Vec<_> = (0..4)
+///         .map(|i| {
+///             let arena = &arena;
+///             s.spawn(move || {
+///                 let lhs = arena.alloc(Expr::Num(i));
+///                 let rhs = arena.alloc(Expr::Num(1));
+///                 &*arena.alloc(Expr::Add(lhs, rhs))
+///             })
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect()
+/// });
+/// assert_eq!(*sums[3], Expr::Add(&Expr::Num(3), &Expr::Num(1)));
+/// ```
+///
+/// 啟用 `allocator-api2` 特性後，`&SyncBump` 實現了 `allocator_api2::alloc::Allocator`，
+/// 可以作為 `Vec`、`Box`、`hashbrown::HashMap` 等集合的分配器。
+///
+/// `MIN_ALIGN` 是每次分配的最小對齊，必須是不大於 16 的 2 的冪。
+/// 如果絕大多數分配的對齊都相同（比如都是 8），把它設為這個值可以省去快速路徑上的對齊計算。
 #[derive(Debug)]
 pub struct SyncBump<const MIN_ALIGN: usize = 1> {
     /// 原子地指向當前活躍的 Chunk。
@@ -71,10 +122,22 @@ pub struct SyncBump<const MIN_ALIGN: usize = 1> {
     /// 分配上限。`usize::MAX` 表示無上限。
     allocation_limit: AtomicUsize,
 
+    /// 因為對齊而跳過的字節數，見 [`ArenaStats::padding_bytes`]。
+    /// 只有啟用 `padding-stats` 特性時才存在，默認的快速路徑上沒有這個共享計數器。
+    #[cfg(feature = "padding-stats")]
+    padding_bytes: AtomicUsize,
+
     /// 用於慢速路徑（分配新Chunk）的鎖，防止多個線程同時分配新Chunk。
     slow_path_lock: std::sync::Mutex<()>,
 }
 
+impl SyncBump {
+    /// 創建一個空的 SyncBump，第一次分配時才會申請內存。
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 impl<const MIN_ALIGN: usize> Default for SyncBump<MIN_ALIGN> {
     fn default() -> Self {
         Self::with_min_align()
@@ -136,19 +199,26 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         SyncBump {
             current_chunkfooter: AtomicPtr::new(unsafe { EMPTY_CHUNK.get_ptr() }),
             allocation_limit: AtomicUsize::new(usize::MAX),
+            #[cfg(feature = "padding-stats")]
+            padding_bytes: AtomicUsize::new(0),
             slow_path_lock: Mutex::new(()),
         }
     }
 
-    // 指定初始化大小的初始化
+    /// 創建一個預先申請了至少 `capacity` 字節的 SyncBump。
+    ///
+    /// # Panics
+    /// 申請內存失敗時會 panic。
     pub fn with_capacity(capacity: usize) -> Self {
         Self::try_with_capacity(capacity).unwrap_or_else(|_| oom())
     }
 
+    /// [`SyncBump::with_capacity`] 的可失敗版本。
     pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocErr> {
         Self::try_with_min_align_and_capacity(capacity)
     }
 
+    /// 與 [`SyncBump::try_with_capacity`] 相同，保留這個名字是為了與 `bumpalo` 對應。
     pub fn try_with_min_align_and_capacity(capacity: usize) -> Result<Self, AllocErr> {
         assert!(
             MIN_ALIGN.is_power_of_two(),
@@ -163,6 +233,8 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
             return Ok(SyncBump {
                 current_chunkfooter: AtomicPtr::new(unsafe { EMPTY_CHUNK.get_ptr() }),
                 allocation_limit: AtomicUsize::new(usize::MAX),
+                #[cfg(feature = "padding-stats")]
+                padding_bytes: AtomicUsize::new(0),
                 slow_path_lock: Mutex::new(()),
             });
         }
@@ -181,11 +253,68 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         Ok(SyncBump {
             current_chunkfooter: AtomicPtr::new(chunk_footer.as_ptr()),
             allocation_limit: AtomicUsize::new(usize::MAX),
+            #[cfg(feature = "padding-stats")]
+            padding_bytes: AtomicUsize::new(0),
             slow_path_lock: Mutex::new(()),
         })
     }
 
-    // 驻留一个字符串
+    /// 把 `val` 移動到 Arena 中，返回指向它的可變引用。
+    ///
+    /// `val` 的析構函數永遠不會被調用，見 [`SyncBump`] 的文檔。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc<T>(&self, val: T) -> &mut T {
+        self.alloc_with(|| val)
+    }
+
+    /// [`SyncBump::alloc`] 的可失敗版本：分配失敗時返回 `AllocErr`（`val` 會被正常析構）。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc<T>(&self, val: T) -> Result<&mut T, AllocErr> {
+        self.try_alloc_with(|| val)
+    }
+
+    /// 先在 Arena 中預留空間，再調用 `f` 構造值並直接寫入其中。
+    ///
+    /// 與 [`SyncBump::alloc`] 相比，這給了編譯器把大型值直接構造在 Arena 中、
+    /// 而不是先構造在棧上再複製過去的機會。如果 `f` panic，預留的空間只是被浪費掉。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_with<T, F>(&self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.try_alloc_with(f).unwrap_or_else(|_| oom())
+    }
+
+    /// [`SyncBump::alloc_with`] 的可失敗版本。分配失敗時 `f` 不會被調用。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_with<T, F>(&self, f: F) -> Result<&mut T, AllocErr>
+    where
+        F: FnOnce() -> T,
+    {
+        let ptr = self
+            .try_alloc_layout(Layout::new::<T>())?
+            .cast::<T>()
+            .as_ptr();
+        unsafe {
+            ptr::write(ptr, f());
+            Ok(&mut *ptr)
+        }
+    }
+
+    /// 把字符串複製到 Arena 中。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
     #[inline(always)]
     // Clippy 警告我们从 &self 返回 &mut str，但这对于一个分配器是常见且安全的操作。
     // 我们返回的是一块全新的内存，而不是对 SyncBump 内部结构的可变引用。
@@ -198,11 +327,27 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         }
     }
 
+    /// `alloc_str` 的可失敗版本：分配失敗時返回 `AllocErr`，而不是 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_str(&self, src: &str) -> Result<&mut str, AllocErr> {
+        let buffer = self.try_alloc_slice_copy(src.as_bytes())?;
+        unsafe {
+            // 同 alloc_str：輸入本來就是 str，所以一定是合法的 UTF-8
+            Ok(str::from_utf8_unchecked_mut(buffer))
+        }
+    }
+
+    /// 按 `layout` 分配一塊未初始化的內存。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
     #[inline(always)]
     pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
         self.try_alloc_layout(layout).unwrap_or_else(|_| oom())
     }
 
+    /// [`SyncBump::alloc_layout`] 的可失敗版本。
     #[inline(always)]
     pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
         if let Some(p) = self.try_alloc_layout_fast(layout) {
@@ -278,6 +423,7 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
                     // 成功！沒有其他線程在我們計算期間修改 `top` 指針。
                     // 我們成功地 "搶" 到了這塊內存。
                     // `aligned_ptr` 現在指向我們分配到的內存的起始位置。
+                    self.add_padding((ptr as usize - aligned_ptr as usize) - layout.size());
                     return Some(unsafe { NonNull::new_unchecked(aligned_ptr) });
                 }
                 Err(_) => {
@@ -403,13 +549,14 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         let allocated = self.allocated_bytes();
 
         // 步驟 3: 執行純計算
-        if allocated > limit {
-            None
-        } else {
-            Some(limit - allocated) // usize::abs_diff in new versions
-        }
+        // 上限可能在運行時被調低到已分配的字節數以下，這時剩餘額度是 0，
+        // 而不是「無上限」。
+        Some(limit.saturating_sub(allocated))
     }
 
+    /// 返回所有 chunk 的總容量（字節），而不是其中已經被使用的部分。
+    ///
+    /// 真正被使用和被浪費的字節數見 [`SyncBump::stats`]。
     pub fn allocated_bytes(&self) -> usize {
         // 步驟 1: 原子性地 `load` 當前 chunk 的指針
         // 我們使用 `Acquire` 語義，因為我們接下來要讀取這個指針指向的內存。
@@ -427,9 +574,113 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         }
     }
 
-    /// 這裡使用Acquire,因為假設未來我添加了動態調整allocation_limit
-    /// 那麼這裡就會需要“acquire 一個 release"
-    /// 且對於”冷路徑“，Relaxed帶來的性能提升有限
+    /// 記錄一次分配因為對齊而跳過的字節數。沒有啟用 `padding-stats` 特性時什麼都不做。
+    ///
+    /// 計數器是所有線程共享的，所以只在真正有填充時才去碰它。
+    #[inline(always)]
+    fn add_padding(&self, bytes: usize) {
+        #[cfg(feature = "padding-stats")]
+        if bytes != 0 {
+            self.padding_bytes.fetch_add(bytes, SyncOrdering::Relaxed);
+        }
+        #[cfg(not(feature = "padding-stats"))]
+        let _ = bytes;
+    }
+
+    /// 撤銷 [`SyncBump::add_padding`] 記錄過的字節：這些字節已經被還給了 chunk。
+    #[cfg(feature = "allocator-api2")]
+    #[inline(always)]
+    fn sub_padding(&self, bytes: usize) {
+        #[cfg(feature = "padding-stats")]
+        if bytes != 0 {
+            self.padding_bytes.fetch_sub(bytes, SyncOrdering::Relaxed);
+        }
+        #[cfg(not(feature = "padding-stats"))]
+        let _ = bytes;
+    }
+
+    /// 遍歷所有 chunk，統計 Arena 的內存使用情況。
+    ///
+    /// 與 [`SyncBump::allocated_bytes`] 只報告總容量不同，這裡會區分真正被使用的字節、
+    /// 當前 chunk 中還可以分配的字節，以及被浪費的字節。
+    /// 其他線程可以在統計期間繼續分配，所以結果是一個近似的快照。
+    pub fn stats(&self) -> ArenaStats {
+        let mut stats = ArenaStats {
+            #[cfg(feature = "padding-stats")]
+            padding_bytes: Some(self.padding_bytes.load(SyncOrdering::Relaxed)),
+            ..ArenaStats::default()
+        };
+        let current = self.current_chunkfooter.load(SyncOrdering::Acquire);
+        let mut chunk = current;
+        unsafe {
+            while !(*chunk).is_empty() {
+                let chunk_ref = &*chunk;
+                let bottom = chunk_ref.bottom.as_ptr() as usize;
+                let top = chunk_ref.top.load(SyncOrdering::Acquire) as usize;
+                let end = chunk as usize;
+
+                stats.chunk_count += 1;
+                stats.capacity_bytes += end - bottom;
+                stats.used_bytes += end - top;
+                if chunk == current {
+                    stats.free_bytes = top - bottom;
+                } else {
+                    stats.abandoned_bytes += top - bottom;
+                }
+                chunk = chunk_ref.prev.as_ptr();
+            }
+        }
+        stats
+    }
+
+    /// 按從新到舊的順序遍歷每個 chunk 中已經被使用的內存區域。
+    ///
+    /// 每一項是一個 chunk 從 `top` 到 footer 之間的字節，也就是這個 chunk 中所有已經分配出去的內存。
+    /// 與 `bumpalo` 一樣，分配是從高地址向低地址進行的，所以在一段區域內，
+    /// 越新的分配位於越低的地址。只要所有分配的對齊都不超過 16 字節，
+    /// 它們就按分配順序緊密排列，中間只有為了對齊而跳過的字節；
+    /// 這些字節沒有被初始化，所以每一項都是 `[MaybeUninit<u8>]`。
+    ///
+    /// 需要 `&mut self`：借用檢查保證了此時沒有其他線程在分配，也沒有任何分配出來的引用仍然存活。
+    ///
+    /// ```
+    /// use interb::SyncBump;
+    ///
+    /// let mut arena = SyncBump::new();
+    /// arena.alloc_str("world");
+    /// arena.alloc_str("hello ");
+    ///
+    /// let chunks: Vec<_> = arena.iter_allocated_chunks().collect();
+    /// assert_eq!(chunks.len(), 1);
+    /// // 最新的分配在最前面
+    /// let bytes: Vec<u8> = chunks[0].iter().map(|b| unsafe { b.assume_init() }).collect();
+    /// assert_eq!(bytes, b"hello world");
+    /// ```
+    pub fn iter_allocated_chunks(&mut self) -> ChunkIter<'_, MIN_ALIGN> {
+        // 安全性：`&mut self` 保證了沒有並發的分配，也沒有仍然存活的分配結果。
+        ChunkIter {
+            raw: unsafe { self.iter_allocated_chunks_raw() },
+        }
+    }
+
+    /// [`SyncBump::iter_allocated_chunks`] 的裸指針版本，只需要 `&self`。
+    ///
+    /// 每一項是 `(起始地址, 長度)`，描述一個 chunk 中已經被使用的區域。
+    ///
+    /// # Safety
+    /// 迭代器本身只讀取 chunk 的元數據，但返回的區域中的內存可能正在被其他線程寫入，
+    /// 或者被某個 `&mut T` 獨占借用。調用者必須保證在通過這些指針讀取內存時，
+    /// 沒有並發的分配和寫入，也沒有與之衝突的活躍引用。
+    pub unsafe fn iter_allocated_chunks_raw(&self) -> ChunkRawIter<'_, MIN_ALIGN> {
+        ChunkRawIter {
+            footer: self.current_chunkfooter.load(SyncOrdering::Acquire),
+            _bump: PhantomData,
+        }
+    }
+
+    /// 返回分配上限（字節），`None` 表示無上限。
+    // 這裡使用Acquire,與 `set_allocation_limit` 中的 Release 配對，
+    // 且對於”冷路徑“，Relaxed帶來的性能提升有限
     pub fn allocation_limit(&self) -> Option<usize> {
         match self.allocation_limit.load(SyncOrdering::Acquire) {
             usize::MAX => None,
@@ -437,6 +688,70 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
         }
     }
 
+    /// 設置分配上限（以字節為單位，計算的是所有 chunk 的總容量），`None` 表示無上限。
+    ///
+    /// 上限只會影響之後新 chunk 的分配：已經分配的 chunk 不會被釋放，
+    /// 當前 chunk 中剩餘的空間也仍然可以繼續使用。
+    /// 如果新的上限低於已經分配的字節數，之後所有需要新 chunk 的分配都會失敗。
+    pub fn set_allocation_limit(&self, limit: Option<usize>) {
+        // `usize::MAX` 是代表 `None` 的哨兵值
+        self.allocation_limit
+            .store(limit.unwrap_or(usize::MAX), SyncOrdering::Release);
+    }
+
+    /// 釋放 Arena 中的所有分配，以便重用內存。
+    ///
+    /// 只保留容量最大的那個 chunk，其餘的 chunk 都歸還給全局分配器；
+    /// 保留的 chunk 的 `top` 被移回頂端，之後的分配會從頭開始使用它。
+    /// 與 `bumpalo` 一樣，這裡不會運行任何析構函數。
+    ///
+    /// 需要 `&mut self`：借用檢查保證了此時沒有任何從這個 SyncBump 分配出來的引用仍然存活。
+    /// 分配上限保持不變。
+    pub fn reset(&mut self) {
+        let current = *self.current_chunkfooter.get_mut();
+        if unsafe { (*current).is_empty() } {
+            return;
+        }
+
+        // 步驟 1: 找出容量最大的 chunk。容量相同時保留較新的那個。
+        let mut largest = current;
+        let mut chunk = current;
+        unsafe {
+            while !(*chunk).is_empty() {
+                if (*chunk).layout.size() > (*largest).layout.size() {
+                    largest = chunk;
+                }
+                chunk = (*chunk).prev.as_ptr();
+            }
+        }
+
+        // 步驟 2: 釋放其餘的 chunk。footer 就在 chunk 內部，所以要先讀出 `prev`。
+        let mut chunk = current;
+        unsafe {
+            while !(*chunk).is_empty() {
+                let prev = (*chunk).prev.as_ptr();
+                if chunk != largest {
+                    dealloc((*chunk).bottom.as_ptr(), (*chunk).layout);
+                }
+                chunk = prev;
+            }
+        }
+
+        // 步驟 3: 把保留的 chunk 變成鏈表中唯一的一個，並把 `top` 移回頂端。
+        unsafe {
+            let footer = &mut *largest;
+            let top = round_mut_ptr_down_to(largest.cast::<u8>(), MIN_ALIGN);
+            footer.prev = NonNull::new_unchecked(EMPTY_CHUNK.get_ptr());
+            footer.allocated_bytes = largest as usize - footer.bottom.as_ptr() as usize;
+            *footer.top.get_mut() = top;
+        }
+        *self.current_chunkfooter.get_mut() = largest;
+        #[cfg(feature = "padding-stats")]
+        {
+            *self.padding_bytes.get_mut() = 0;
+        }
+    }
+
     fn new_chunk_memory_details(
         new_size_without_footer: Option<usize>,
         requested_layout: Layout,
@@ -566,22 +881,357 @@ impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
             .unwrap_or(true)
     }
 
+    /// 把 `src` 按位複製到 Arena 中。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
     #[inline(always)]
     #[allow(clippy::mut_from_ref)]
     pub fn alloc_slice_copy<T>(&self, src: &[T]) -> &mut [T]
+    where
+        T: Copy,
+    {
+        self.try_alloc_slice_copy(src).unwrap_or_else(|_| oom())
+    }
+
+    /// `alloc_slice_copy` 的可失敗版本。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_slice_copy<T>(&self, src: &[T]) -> Result<&mut [T], AllocErr>
     where
         T: Copy,
     {
         let layout = Layout::for_value(src);
-        let dst = self.alloc_layout(layout).cast::<T>();
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
 
         unsafe {
             ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), src.len());
-            slice::from_raw_parts_mut(dst.as_ptr(), src.len())
+            Ok(slice::from_raw_parts_mut(dst.as_ptr(), src.len()))
         }
     }
+
+    /// 把 `src` 中的每個元素克隆到 Arena 中。
+    ///
+    /// 如果某次 `clone` panic，已經克隆出來的元素不會被析構，只是被洩漏。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_clone<T>(&self, src: &[T]) -> &mut [T]
+    where
+        T: Clone,
+    {
+        self.try_alloc_slice_clone(src).unwrap_or_else(|_| oom())
+    }
+
+    /// [`SyncBump::alloc_slice_clone`] 的可失敗版本。分配失敗時不會調用任何 `clone`。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_slice_clone<T>(&self, src: &[T]) -> Result<&mut [T], AllocErr>
+    where
+        T: Clone,
+    {
+        self.try_alloc_slice_fill_with(src.len(), |i| src[i].clone())
+    }
+
+    /// 分配一個長度為 `len`、每個元素都是 `value` 的切片。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_copy<T>(&self, len: usize, value: T) -> &mut [T]
+    where
+        T: Copy,
+    {
+        self.try_alloc_slice_fill_copy(len, value)
+            .unwrap_or_else(|_| oom())
+    }
+
+    /// [`SyncBump::alloc_slice_fill_copy`] 的可失敗版本。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_slice_fill_copy<T>(&self, len: usize, value: T) -> Result<&mut [T], AllocErr>
+    where
+        T: Copy,
+    {
+        self.try_alloc_slice_fill_with(len, |_| value)
+    }
+
+    /// 分配一個長度為 `len` 的切片，第 `i` 個元素由 `f(i)` 構造。
+    ///
+    /// # Panics
+    /// 申請內存失敗（或超出分配上限）時會 panic。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, f: F) -> &mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        self.try_alloc_slice_fill_with(len, f)
+            .unwrap_or_else(|_| oom())
+    }
+
+    /// [`SyncBump::alloc_slice_fill_with`] 的可失敗版本。分配失敗時 `f` 不會被調用。
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_slice_fill_with<T, F>(
+        &self,
+        len: usize,
+        mut f: F,
+    ) -> Result<&mut [T], AllocErr>
+    where
+        F: FnMut(usize) -> T,
+    {
+        let layout = Layout::array::<T>(len).map_err(|_| AllocErr)?;
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+        unsafe {
+            for i in 0..len {
+                // 逐個寫入：如果 `f` panic，已經寫入的元素只是被洩漏，而不會被當作已初始化的切片讀取。
+                ptr::write(dst.as_ptr().add(i), f(i));
+            }
+            Ok(slice::from_raw_parts_mut(dst.as_ptr(), len))
+        }
+    }
+
+    /// 嘗試把當前 chunk 的 `top` 從 `expected` 移動到 `new`，成功時返回 true。
+    ///
+    /// 只有 `expected` 恰好是當前 chunk 的 `top` 時才會成功，
+    /// 用於把最近的一次分配（的一部分）原地還給 chunk。
+    pub(crate) fn try_move_top(&self, expected: *mut u8, new: *mut u8) -> bool {
+        let chunk_ref = unsafe { &*self.current_chunkfooter.load(SyncOrdering::Acquire) };
+        !chunk_ref.is_empty()
+            && chunk_ref
+                .top
+                .compare_exchange(expected, new, SyncOrdering::AcqRel, SyncOrdering::Acquire)
+                .is_ok()
+    }
+
+    /// 在一次分配失敗之後，判斷失敗是否是由分配上限造成的：
+    /// 也就是說，慢速路徑願意嘗試的最小的新 chunk 都已經放不進剩餘的額度了。
+    pub(crate) fn allocation_limit_blocks(&self, layout: Layout) -> bool {
+        let (Some(limit), Some(remaining)) =
+            (self.allocation_limit(), self.allocation_limit_remaining())
+        else {
+            return false;
+        };
+        let allocated = self.allocated_bytes();
+
+        // 與 alloc_layout_slow 保持一致：只有在還沒有分配過任何 chunk、且上限很小的時候，
+        // 才會繞過默認的最小 chunk 大小。
+        let min_chunk_size = if allocated == 0 && limit < DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER {
+            layout.size()
+        } else {
+            layout.size().max(DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER)
+        };
+        match Self::new_chunk_memory_details(Some(min_chunk_size), layout) {
+            Some(details) => !Self::chunk_fits_under_limit(Some(remaining), details),
+            None => true,
+        }
+    }
+}
+
+/// 原地調整最近一次分配的輔助方法，供 `allocator-api2` 特性中的 `Allocator` 實現使用。
+///
+/// 只有位於當前 chunk `top` 處的那一次分配（也就是最近的一次）才能被原地釋放、收縮或擴展。
+/// 判斷和修改 `top` 是同一次 CAS：如果在這期間有其他線程分配了新的內存，
+/// CAS 就會失敗，這時退化為普通的「分配新內存再複製」（或者什麼都不做）。
+#[cfg(feature = "allocator-api2")]
+impl<const MIN_ALIGN: usize> SyncBump<MIN_ALIGN> {
+    /// 如果 `ptr` 是最近的一次分配，就把它佔用的空間還給當前 chunk；否則什麼都不做。
+    ///
+    /// # Safety
+    /// `ptr` 必須是這個 SyncBump 按 `layout` 分配的、仍然有效的內存，並且之後不再被使用。
+    pub(crate) unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        // 分配的起始地址總是按 MIN_ALIGN 對齊的，歸還時也按 MIN_ALIGN 向上取整，
+        // 這樣 `top` 始終保持對齊，並且不會越過這次分配原本佔用的範圍。
+        let size = unsafe { round_up_to_unchecked(layout.size(), MIN_ALIGN) };
+        if self.try_move_top(ptr.as_ptr(), ptr.as_ptr().wrapping_add(size)) {
+            // 分配時記錄的尾部填充隨著這段內存一起被還了回去。
+            self.sub_padding(size - layout.size());
+        }
+    }
+
+    /// 把一次分配收縮為 `new_layout`。
+    ///
+    /// 如果它是最近的一次分配，並且能收回至少一半的空間，就把數據向上挪到新的位置，
+    /// 再把 `top` 移上去；否則直接返回原來的指針。
+    ///
+    /// # Safety
+    /// `ptr` 必須是這個 SyncBump 按 `old_layout` 分配的、仍然有效的內存，
+    /// 並且 `new_layout.size() <= old_layout.size()`。
+    pub(crate) unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocErr> {
+        let old_size = old_layout.size();
+        let new_size = new_layout.size();
+        debug_assert!(new_size <= old_size);
+
+        // 新的對齊更大：原地址恰好滿足時直接使用，否則只能重新分配。
+        if new_layout.align() > old_layout.align() {
+            if (ptr.as_ptr() as usize).is_multiple_of(new_layout.align()) {
+                self.add_padding(old_size - new_size);
+                return Ok(ptr);
+            }
+            let new_ptr = self.try_alloc_layout(new_layout)?;
+            unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size) };
+            return Ok(new_ptr);
+        }
+
+        // 實際能收回的字節數：新地址必須同時滿足新的對齊和 MIN_ALIGN。
+        let align = new_layout.align().max(MIN_ALIGN);
+        let delta = (old_size - new_size) & !(align - 1);
+
+        // 只有收回的空間足夠多時才值得複製。`delta >= new_size` 同時保證了新舊兩段不重疊，
+        // 所以可以先複製、再發布新的 `top`：CAS 失敗時原來的數據仍然完好。
+        if delta >= old_size / 2 && delta >= new_size && delta > 0 {
+            let new_ptr = ptr.as_ptr().wrapping_add(delta);
+            unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr, new_size) };
+            if self.try_move_top(ptr.as_ptr(), new_ptr) {
+                // 前 `delta` 字節還給了 chunk，新數據之後剩下的部分變成了填充。
+                self.add_padding(old_size - new_size - delta);
+                return Ok(unsafe { NonNull::new_unchecked(new_ptr) });
+            }
+        }
+        // 原地收縮：新的結尾之後的空間不會再被使用。
+        self.add_padding(old_size - new_size);
+        Ok(ptr)
+    }
+
+    /// 把一次分配擴展為 `new_layout`。
+    ///
+    /// 如果它是最近的一次分配，並且當前 chunk 中還有足夠的空間，就把 `top` 繼續向下移動，
+    /// 再把數據挪到新的起始地址：原來佔用的空間被新的分配重用，不會被浪費。
+    /// 否則分配一塊新的內存並複製。
+    ///
+    /// # Safety
+    /// `ptr` 必須是這個 SyncBump 按 `old_layout` 分配的、仍然有效的內存，
+    /// 並且 `new_layout.size() >= old_layout.size()`。
+    pub(crate) unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocErr> {
+        let old_size = old_layout.size();
+        let new_size = new_layout.size();
+        debug_assert!(new_size >= old_size);
+
+        let chunk_ref = unsafe { &*self.current_chunkfooter.load(SyncOrdering::Acquire) };
+        if !chunk_ref.is_empty() {
+            let start = chunk_ref.bottom.as_ptr();
+            let extra = new_size - old_size;
+            let align = new_layout.align().max(MIN_ALIGN);
+            let available = (ptr.as_ptr() as usize).wrapping_sub(start as usize);
+
+            // `ptr` 不在當前 chunk 中時 `available` 會是一個無意義的值，
+            // 但那樣的話 `top` 一定不等於 `ptr`，下面的 CAS 一定會失敗。
+            if extra <= available {
+                let new_ptr = round_mut_ptr_down_to(ptr.as_ptr().wrapping_sub(extra), align);
+                if new_ptr >= start
+                    && chunk_ref
+                        .top
+                        .compare_exchange(
+                            ptr.as_ptr(),
+                            new_ptr,
+                            SyncOrdering::AcqRel,
+                            SyncOrdering::Acquire,
+                        )
+                        .is_ok()
+                {
+                    // 新舊兩段可能重疊，所以使用 `copy` 而不是 `copy_nonoverlapping`。
+                    unsafe { ptr::copy(ptr.as_ptr(), new_ptr, old_size) };
+                    // 向下對齊新地址時多跳過的字節。
+                    self.add_padding(
+                        (ptr.as_ptr() as usize - new_ptr as usize) - (new_size - old_size),
+                    );
+                    return Ok(unsafe { NonNull::new_unchecked(new_ptr) });
+                }
+            }
+        }
+
+        let new_ptr = self.try_alloc_layout(new_layout)?;
+        unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_size) };
+        Ok(new_ptr)
+    }
+}
+
+/// [`SyncBump::stats`] 和 [`Interner::arena_stats`](crate::Interner::arena_stats) 返回的內存統計。
+///
+/// 所有數值都以字節為單位（`chunk_count` 除外），並且滿足
+/// `capacity_bytes == used_bytes + free_bytes + abandoned_bytes`。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ArenaStats {
+    /// chunk 的數量。
+    pub chunk_count: usize,
+    /// 所有 chunk 的總容量，與 [`SyncBump::allocated_bytes`] 相同。
+    pub capacity_bytes: usize,
+    /// 已經被分配出去的字節數，包括對齊填充。
+    ///
+    /// [`LocalBump`](crate::LocalBump) 預留的子塊整個計入這裡，
+    /// 包括子塊內部的對齊填充和沒有被還回來的尾部。
+    pub used_bytes: usize,
+    /// 當前 chunk 中還可以分配的字節數。
+    pub free_bytes: usize,
+    /// 舊 chunk 末尾被放棄的字節數：一次分配放不下時會換到新的 chunk，舊 chunk 剩下的空間不會再被使用。
+    pub abandoned_bytes: usize,
+    /// 為了滿足對齊而跳過的字節數，已經包含在 `used_bytes` 中。
+    ///
+    /// 精確地統計填充需要在每次有填充的分配上更新一個所有線程共享的計數器，
+    /// 所以只有啟用 `padding-stats` 特性時才會統計，否則總是 `None`。
+    pub padding_bytes: Option<usize>,
+}
+
+/// [`SyncBump::iter_allocated_chunks`] 返回的迭代器。
+#[derive(Debug)]
+pub struct ChunkIter<'a, const MIN_ALIGN: usize = 1> {
+    raw: ChunkRawIter<'a, MIN_ALIGN>,
+}
+
+impl<'a, const MIN_ALIGN: usize> Iterator for ChunkIter<'a, MIN_ALIGN> {
+    type Item = &'a [MaybeUninit<u8>];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ptr, len) = self.raw.next()?;
+        // 安全性：創建迭代器時的 `&mut SyncBump` 保證了這段內存在 `'a` 期間不會被寫入。
+        Some(unsafe { slice::from_raw_parts(ptr as *const MaybeUninit<u8>, len) })
+    }
+}
+
+impl<const MIN_ALIGN: usize> FusedIterator for ChunkIter<'_, MIN_ALIGN> {}
+
+/// [`SyncBump::iter_allocated_chunks_raw`] 返回的迭代器。
+#[derive(Debug)]
+pub struct ChunkRawIter<'a, const MIN_ALIGN: usize = 1> {
+    footer: *mut ChunkFooter,
+    _bump: PhantomData<&'a SyncBump<MIN_ALIGN>>,
 }
 
+impl<const MIN_ALIGN: usize> Iterator for ChunkRawIter<'_, MIN_ALIGN> {
+    type Item = (*mut u8, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // chunk 在 SyncBump 被重置或析構之前一直有效，而迭代器借用著 SyncBump。
+        let footer = unsafe { &*self.footer };
+        if footer.is_empty() {
+            return None;
+        }
+        // chunk 的末尾（也就是 footer 的地址）總是按 SUPPORTED_ITER_ALIGNMENT 對齊的，
+        // 所以不超過這個對齊的分配都從這裡開始緊密地向下排列。
+        debug_assert_eq!(self.footer as usize % SUPPORTED_ITER_ALIGNMENT, 0);
+        let (top, len) = footer.get_current_top_and_allocated_size();
+        self.footer = footer.prev.as_ptr();
+        Some((top as *mut u8, len))
+    }
+}
+
+impl<const MIN_ALIGN: usize> FusedIterator for ChunkRawIter<'_, MIN_ALIGN> {}
+
 /// The memory size and alignment details for a potential new chunk
 /// allocation.
 #[derive(Debug, Clone, Copy)]
@@ -636,7 +1286,7 @@ fn round_mut_ptr_down_to(ptr: *mut u8, divisor: usize) -> *mut u8 {
 
 #[inline(never)]
 #[cold]
-fn oom() -> ! {
+pub(crate) fn oom() -> ! {
     panic!("out of memory")
 }
 
@@ -655,3 +1305,255 @@ impl fmt::Display for AllocErr {
         f.write_str("memory allocation failed")
     }
 }
+
+impl std::error::Error for AllocErr {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocation_limit_setter() {
+        let bump: SyncBump = SyncBump::default();
+        assert_eq!(bump.allocation_limit(), None);
+
+        bump.set_allocation_limit(Some(4096));
+        assert_eq!(bump.allocation_limit(), Some(4096));
+
+        bump.set_allocation_limit(None);
+        assert_eq!(bump.allocation_limit(), None);
+    }
+
+    #[test]
+    fn test_bypass_min_chunk_size_for_small_limits() {
+        // 上限比默认的最小 chunk 还小：第一个 chunk 会绕过最小 chunk 大小，
+        // 按上限能容纳的尺寸来分配，而不是直接失败。
+        let limit = DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER / 4;
+        let bump: SyncBump = SyncBump::default();
+        bump.set_allocation_limit(Some(limit));
+
+        let s = bump.try_alloc_str("small").unwrap();
+        assert_eq!(s, "small");
+        assert!(bump.allocated_bytes() > 0);
+        assert!(bump.allocated_bytes() <= limit);
+
+        // 已经分配过 chunk 之后就不再绕过：需要新 chunk 的分配会因为上限而失败
+        let big = vec![0u8; bump.allocated_bytes()];
+        assert_eq!(bump.try_alloc_slice_copy(&big), Err(AllocErr));
+        assert!(bump.allocation_limit_blocks(Layout::for_value(&big[..])));
+        assert!(bump.allocated_bytes() <= limit);
+    }
+
+    #[test]
+    fn test_allocation_limit_below_usage_and_raise() {
+        let bump: SyncBump = SyncBump::with_capacity(64);
+        let allocated = bump.allocated_bytes();
+
+        // 把上限调到已分配的字节数以下：剩余额度为 0，而不是无上限
+        bump.set_allocation_limit(Some(allocated / 2));
+        assert_eq!(bump.allocation_limit_remaining(), Some(0));
+        let big = vec![1u8; allocated * 2];
+        assert_eq!(bump.try_alloc_slice_copy(&big), Err(AllocErr));
+
+        // 取消上限后又可以继续分配
+        bump.set_allocation_limit(None);
+        assert!(!bump.allocation_limit_blocks(Layout::for_value(&big[..])));
+        assert_eq!(bump.try_alloc_slice_copy(&big).unwrap(), &big[..]);
+    }
+
+    #[test]
+    fn test_alloc_typed_values() {
+        let bump = SyncBump::new();
+        let byte = bump.alloc(1u8);
+        let word = bump.alloc(0x1234_5678_9abc_def0u64);
+        let big = bump.alloc_with(|| [7u32; 256]);
+        *byte += 1;
+
+        assert_eq!(*byte, 2);
+        assert_eq!(*word, 0x1234_5678_9abc_def0);
+        assert_eq!(word as *mut u64 as usize % mem::align_of::<u64>(), 0);
+        assert!(big.iter().all(|&x| x == 7));
+        assert_eq!(*bump.try_alloc("tuple").unwrap(), "tuple");
+        assert_eq!(*bump.alloc(()), ());
+    }
+
+    #[test]
+    fn test_alloc_slices() {
+        let bump: SyncBump<8> = SyncBump::with_min_align();
+        assert_eq!(bump.alloc_slice_fill_copy(3, 9u16), [9, 9, 9]);
+        assert_eq!(bump.alloc_slice_fill_with(4, |i| i * i), [0, 1, 4, 9]);
+        assert!(bump.alloc_slice_fill_copy(0, 1u8).is_empty());
+
+        let names = vec![String::from("a"), String::from("bc")];
+        let cloned = bump.alloc_slice_clone(&names);
+        cloned[0].push('!');
+        assert_eq!(cloned, ["a!", "bc"]);
+        assert_eq!(names, ["a", "bc"]);
+        // 克隆出来的 String 不会被 SyncBump 析构，这里手动释放以免测试泄漏内存
+        for s in cloned.iter_mut() {
+            drop(mem::take(s));
+        }
+    }
+
+    #[test]
+    fn test_try_alloc_fails_without_calling_constructor() {
+        let bump = SyncBump::new();
+        bump.set_allocation_limit(Some(0));
+
+        assert_eq!(bump.try_alloc(1u32), Err(AllocErr));
+        let mut called = false;
+        assert!(
+            bump.try_alloc_with(|| {
+                called = true;
+                0u64
+            })
+            .is_err()
+        );
+        assert!(!called);
+        assert!(bump.try_alloc_slice_clone(&[1, 2, 3]).is_err());
+        assert!(bump.try_alloc_slice_fill_copy(4, 0u8).is_err());
+        assert_eq!(bump.allocated_bytes(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_alloc() {
+        use std::collections::HashSet;
+        use std::thread;
+
+        let bump = SyncBump::new();
+        let addresses: Vec<Vec<usize>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8u64)
+                .map(|t| {
+                    let bump = &bump;
+                    s.spawn(move || {
+                        (0..2000u64)
+                            .map(|i| {
+                                let value = bump.alloc(t * 10_000 + i);
+                                let slice = bump.alloc_slice_fill_copy(3, i as u8);
+                                assert_eq!(*slice, [i as u8; 3]);
+                                value as *mut u64 as usize
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // 所有线程拿到的内存互不重叠，并且写入的值都没有被覆盖
+        let mut seen = HashSet::new();
+        for (t, thread_addresses) in addresses.iter().enumerate() {
+            for (i, &address) in thread_addresses.iter().enumerate() {
+                assert!(seen.insert(address));
+                assert_eq!(address % mem::align_of::<u64>(), 0);
+                let value = unsafe { *(address as *const u64) };
+                assert_eq!(value, t as u64 * 10_000 + i as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_keeps_largest_chunk() {
+        let mut bump = SyncBump::new();
+        // 空的 SyncBump 上 reset 什么都不做
+        bump.reset();
+        assert_eq!(bump.allocated_bytes(), 0);
+
+        for _ in 0..100 {
+            bump.alloc_slice_fill_copy(100, 0u8);
+        }
+        let before = bump.allocated_bytes();
+        bump.reset();
+        let kept = bump.allocated_bytes();
+        assert!(kept > 0 && kept < before);
+
+        // 保留的 chunk 被完整地重用：填满它也不需要申请新的 chunk
+        let reused = bump.alloc_slice_fill_copy(kept, 1u8);
+        assert!(reused.iter().all(|&b| b == 1));
+        assert_eq!(bump.allocated_bytes(), kept);
+
+        // 之后的分配照常申请新的 chunk
+        bump.alloc(2u64);
+        assert!(bump.allocated_bytes() > kept);
+    }
+
+    #[test]
+    fn test_stats() {
+        let bump: SyncBump = SyncBump::new();
+        assert_eq!(
+            bump.stats(),
+            ArenaStats {
+                padding_bytes: cfg!(feature = "padding-stats").then_some(0),
+                ..ArenaStats::default()
+            }
+        );
+
+        let bump: SyncBump = SyncBump::with_capacity(1024);
+        let capacity = bump.allocated_bytes();
+        bump.alloc(1u8);
+        // 一个 u8 之后再分配 u64：跳过 7 个字节来对齐
+        bump.alloc(2u64);
+        let stats = bump.stats();
+        assert_eq!(stats.chunk_count, 1);
+        assert_eq!(stats.capacity_bytes, capacity);
+        assert_eq!(stats.used_bytes, 16);
+        assert_eq!(
+            stats.padding_bytes,
+            cfg!(feature = "padding-stats").then_some(7)
+        );
+        assert_eq!(stats.free_bytes, capacity - 16);
+        assert_eq!(stats.abandoned_bytes, 0);
+
+        // 当前 chunk 放不下时换到新的 chunk，旧 chunk 剩下的空间被放弃
+        bump.alloc_slice_fill_copy(capacity, 0u8);
+        let stats = bump.stats();
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.abandoned_bytes, capacity - 16);
+        assert_eq!(stats.capacity_bytes, bump.allocated_bytes());
+        assert_eq!(
+            stats.capacity_bytes,
+            stats.used_bytes + stats.free_bytes + stats.abandoned_bytes
+        );
+    }
+
+    #[test]
+    fn test_iter_allocated_chunks() {
+        let mut bump: SyncBump = SyncBump::new();
+        assert_eq!(bump.iter_allocated_chunks().count(), 0);
+
+        // 分配足够多的数据，跨越多个 chunk
+        for i in 0..200u8 {
+            bump.alloc_slice_fill_copy(50, i);
+        }
+        let stats = bump.stats();
+        let raw: Vec<(*mut u8, usize)> = unsafe { bump.iter_allocated_chunks_raw() }.collect();
+
+        let chunks: Vec<&[MaybeUninit<u8>]> = bump.iter_allocated_chunks().collect();
+        assert_eq!(chunks.len(), stats.chunk_count);
+        assert!(chunks.len() > 1);
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).sum::<usize>(),
+            stats.used_bytes
+        );
+        assert!(
+            chunks
+                .iter()
+                .zip(&raw)
+                .all(|(c, &(ptr, len))| c.as_ptr() as *mut u8 == ptr && c.len() == len)
+        );
+
+        // 从最新的 chunk 开始，每段区域内越新的分配越靠前：拼起来正好是倒序的分配结果
+        let bytes: Vec<u8> = chunks
+            .iter()
+            .flat_map(|c| c.iter().map(|b| unsafe { b.assume_init() }))
+            .collect();
+        let expected: Vec<u8> = (0..200u8).rev().flat_map(|i| [i; 50]).collect();
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_no_limit_is_not_blocking() {
+        let bump: SyncBump = SyncBump::default();
+        assert!(!bump.allocation_limit_blocks(Layout::new::<u64>()));
+    }
+}
@@ -0,0 +1,266 @@
+// src/table.rs
+
+//! 一個只追加 (append-only) 的分段符號表，用於從 Symbol 反查被駐留的鍵。
+//!
+//! 表由一組容量按 2 的冪增長的桶 (bucket) 組成：第 0 個桶有 `FIRST_BUCKET_LEN` 個槽位，
+//! 之後每個桶都是前一個的兩倍。桶一旦分配就不會移動，也不會被釋放（直到整張表被析構），
+//! 所以讀取方不需要任何鎖：`get` 只是幾次帶 Acquire 語義的原子讀取，是 wait-free 的。
+
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// 第 0 個桶的槽位數量的以 2 為底的對數。
+const FIRST_BUCKET_BITS: u32 = 5;
+/// 第 0 個桶的槽位數量。
+const FIRST_BUCKET_LEN: usize = 1 << FIRST_BUCKET_BITS;
+/// 桶的總數。所有桶加起來足以覆蓋整個 usize 的下標空間。
+const BUCKET_COUNT: usize = (usize::BITS - FIRST_BUCKET_BITS) as usize;
+
+/// 一個槽位，保存一個 `[T]` 切片的指針和長度。
+///
+/// 寫入方先寫 `len`，再用 Release 語義寫 `ptr`；讀取方用 Acquire 語義讀到非空的 `ptr` 之後，
+/// 就一定能看到與之配對的 `len`。空指針表示這個槽位還沒有被發布。
+struct Slot<T> {
+    ptr: AtomicPtr<T>,
+    len: AtomicUsize,
+}
+
+impl<T> Slot<T> {
+    const fn new() -> Self {
+        Slot {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// 從 Symbol 下標到鍵的切片表示的只追加映射。
+///
+/// 這裡保存的 `'static` 切片與 `Interner` 中的一樣，實際上住在 Interner 的 Arena 裡，
+/// 由持有者保證它們比這張表活得更久。
+pub(super) struct SymbolTable<T> {
+    /// 每個桶指向一段長度為 `bucket_len(i)` 的槽位數組，空指針表示還沒有分配。
+    buckets: [AtomicPtr<Slot<T>>; BUCKET_COUNT],
+    /// 已經被預留出去的下標數量，也就是下一個 Symbol 的編號。
+    len: AtomicUsize,
+}
+
+impl<T> SymbolTable<T> {
+    pub(super) fn new() -> Self {
+        SymbolTable {
+            buckets: [const { AtomicPtr::new(ptr::null_mut()) }; BUCKET_COUNT],
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// 返回已經被預留出去的下標數量。
+    ///
+    /// 一個下標在被預留之後、被發布之前的極短時間內，`get` 仍會返回 None；
+    /// 但在那之前，沒有任何人拿得到對應的 Symbol。
+    pub(super) fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// 追加一個切片，返回它的下標。
+    ///
+    /// 可以被多個線程並發調用：下標通過原子操作預留，所以全局唯一且連續。
+    /// 如果表中已經有 `max_len` 個元素，則不做任何修改並返回 None。
+    pub(super) fn try_push(&self, items: &'static [T], max_len: usize) -> Option<usize> {
+        let index = self
+            .len
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |len| {
+                (len < max_len).then_some(len + 1)
+            })
+            .ok()?;
+        let (bucket, offset) = locate(index);
+        let slot = unsafe { &*self.bucket_or_alloc(bucket).add(offset) };
+
+        // 先寫長度，再發布指針。
+        slot.len.store(items.len(), Ordering::Relaxed);
+        slot.ptr.store(items.as_ptr() as *mut T, Ordering::Release);
+        Some(index)
+    }
+
+    /// 根據下標取回切片。wait-free：永遠不會等待任何寫入方。
+    pub(super) fn get(&self, index: usize) -> Option<&'static [T]> {
+        if index >= self.len() {
+            return None;
+        }
+        let (bucket, offset) = locate(index);
+        let bucket_ptr = self.buckets[bucket].load(Ordering::Acquire);
+        if bucket_ptr.is_null() {
+            return None;
+        }
+
+        let slot = unsafe { &*bucket_ptr.add(offset) };
+        let ptr = slot.ptr.load(Ordering::Acquire);
+        if ptr.is_null() {
+            return None;
+        }
+        let len = slot.len.load(Ordering::Relaxed);
+
+        // 安全性：非空的指針只會由 `try_push` 寫入，它和 `len` 一起描述了一個有效的切片。
+        unsafe { Some(slice::from_raw_parts(ptr, len)) }
+    }
+
+    /// 根據一個已經被預留的下標取回切片；如果它還沒有被發布，就自旋等待。
+    ///
+    /// 用於按編號遍歷整張表：下標在預留和發布之間只隔著幾條指令
+    /// （寫入方在預留之前就已經準備好了切片），所以等待是短暫的。
+    ///
+    /// # Panics
+    /// `index` 必須小於 `len()`，否則會 panic。
+    pub(super) fn get_or_wait(&self, index: usize) -> &'static [T] {
+        assert!(index < self.len(), "symbol table index out of bounds");
+        loop {
+            if let Some(s) = self.get(index) {
+                return s;
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// 取得一個桶的指針，如果它還沒有被分配，就分配它。
+    ///
+    /// 多個線程可能同時發現同一個桶為空：它們各自分配，然後用 CAS 競爭發布，
+    /// 失敗的一方釋放自己的那一份，轉而使用勝出者的桶。
+    fn bucket_or_alloc(&self, bucket: usize) -> *mut Slot<T> {
+        let current = self.buckets[bucket].load(Ordering::Acquire);
+        if !current.is_null() {
+            return current;
+        }
+
+        let new_bucket: Box<[Slot<T>]> = (0..bucket_len(bucket)).map(|_| Slot::new()).collect();
+        let new_ptr = Box::into_raw(new_bucket) as *mut Slot<T>;
+        match self.buckets[bucket].compare_exchange(
+            ptr::null_mut(),
+            new_ptr,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new_ptr,
+            Err(winner) => {
+                unsafe { free_bucket(new_ptr, bucket) };
+                winner
+            }
+        }
+    }
+}
+
+impl<T> Drop for SymbolTable<T> {
+    fn drop(&mut self) {
+        for (bucket, bucket_ptr) in self.buckets.iter_mut().enumerate() {
+            let bucket_ptr = *bucket_ptr.get_mut();
+            if !bucket_ptr.is_null() {
+                unsafe { free_bucket(bucket_ptr, bucket) };
+            }
+        }
+    }
+}
+
+/// 第 `bucket` 個桶的槽位數量。
+#[inline]
+const fn bucket_len(bucket: usize) -> usize {
+    FIRST_BUCKET_LEN << bucket
+}
+
+/// 把一個下標拆分為（桶下標，桶內偏移）。
+///
+/// 第 `b` 個桶覆蓋的下標範圍是 `[FIRST * (2^b - 1), FIRST * (2^(b+1) - 1))`，
+/// 所以給下標加上 `FIRST` 之後，最高位的位置就直接給出了桶下標。
+#[inline]
+fn locate(index: usize) -> (usize, usize) {
+    let biased = index
+        .checked_add(FIRST_BUCKET_LEN)
+        .expect("symbol table index overflowed");
+    let bucket = (usize::BITS - 1 - biased.leading_zeros() - FIRST_BUCKET_BITS) as usize;
+    (bucket, biased - bucket_len(bucket))
+}
+
+/// 釋放一個由 `bucket_or_alloc` 分配的桶。
+///
+/// # Safety
+/// `bucket_ptr` 必須來自 `bucket_or_alloc` 中對同一個 `bucket` 的分配，並且之後不再被使用。
+unsafe fn free_bucket<T>(bucket_ptr: *mut Slot<T>, bucket: usize) {
+    let slots = ptr::slice_from_raw_parts_mut(bucket_ptr, bucket_len(bucket));
+    drop(unsafe { Box::from_raw(slots) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_bucket_boundaries() {
+        // 第 0 个桶：[0, 32)
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(31), (0, 31));
+        // 第 1 个桶：[32, 96)
+        assert_eq!(locate(32), (1, 0));
+        assert_eq!(locate(95), (1, 63));
+        // 第 2 个桶：[96, 224)
+        assert_eq!(locate(96), (2, 0));
+        // 最后一个下标也必须落在某个桶内
+        let (bucket, offset) = locate(usize::MAX - FIRST_BUCKET_LEN);
+        assert_eq!(bucket, BUCKET_COUNT - 1);
+        assert!(offset < bucket_len(bucket));
+    }
+
+    #[test]
+    fn test_push_and_get_across_buckets() {
+        let table: SymbolTable<u8> = SymbolTable::new();
+        let strings: Vec<&'static [u8]> = (0..500)
+            .map(|i| &*Box::leak(format!("s{i}").into_bytes().into_boxed_slice()))
+            .collect();
+
+        for (i, s) in strings.iter().enumerate() {
+            assert_eq!(table.try_push(s, usize::MAX), Some(i));
+        }
+        assert_eq!(table.len(), 500);
+        for (i, s) in strings.iter().enumerate() {
+            assert_eq!(table.get(i), Some(*s));
+        }
+        assert_eq!(table.get(500), None);
+        assert_eq!(table.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_try_push_respects_max_len() {
+        let table: SymbolTable<u8> = SymbolTable::new();
+        assert_eq!(table.try_push(b"a", 2), Some(0));
+        assert_eq!(table.try_push(b"b", 2), Some(1));
+
+        // 已满：不预留下标，也不改变长度
+        assert_eq!(table.try_push(b"c", 2), None);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(2), None);
+        assert_eq!(table.get(1), Some(&b"b"[..]));
+    }
+
+    #[test]
+    fn test_concurrent_push_and_get() {
+        use std::thread;
+
+        let table: SymbolTable<u8> = SymbolTable::new();
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        let index = table.try_push(b"x", usize::MAX).unwrap();
+                        // 自己刚刚发布的槽位必须立刻可见
+                        assert_eq!(table.get(index), Some(&b"x"[..]));
+                    }
+                });
+            }
+            // 读取方与写入方并发运行，看到的要么是 None，要么是完整的字符串
+            s.spawn(|| {
+                for i in 0..4000 {
+                    assert!(matches!(table.get(i), None | Some(b"x")));
+                }
+            });
+        });
+        assert_eq!(table.len(), 4000);
+        assert!((0..4000).all(|i| table.get(i) == Some(&b"x"[..])));
+    }
+}
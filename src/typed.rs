@@ -0,0 +1,234 @@
+// src/typed.rs
+
+//! 按命名空間區分的類型化 Symbol。
+//!
+//! 同一個程序裡往往有好幾張符號表：標識符、字符串字面量、文件路徑……
+//! 它們的 Symbol 都是 `u32`，一不小心就會混用。[`TypedInterner<Tag>`] 在 [`Interner`]
+//! 之上加了一個零大小的標籤類型，它產出的 [`TypedSymbol<Tag>`] 只能和同一個標籤的 Symbol 比較，
+//! 也只能在同一個標籤的 Interner 中解析，混用會在編譯期報錯：
+//!
+//! ```compile_fail
+//! use interb::TypedInterner;
+//!
+//! enum Ident {}
+//! enum Path {}
+//!
+//! let idents: TypedInterner<Ident> = TypedInterner::new();
+//! let paths: TypedInterner<Path> = TypedInterner::new();
+//! let sym = idents.intern("main");
+//! paths.resolve(sym); // 錯誤：期望 TypedSymbol<Path>，得到 TypedSymbol<Ident>
+//! ```
+
+use crate::{InternError, Interner, Symbol, SymbolRepr};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// 帶有命名空間標籤 `Tag` 的 Symbol，由 [`TypedInterner<Tag>`] 產生。
+///
+/// 大小與底層的 Symbol 類型 `S` 相同，`Option<TypedSymbol<Tag, S>>` 也是。
+/// `Tag` 只是一個編譯期的標記，通常是一個沒有值的空枚舉，不需要實現任何 trait。
+pub struct TypedSymbol<Tag, S = Symbol> {
+    symbol: S,
+    /// `fn() -> Tag` 讓標籤不影響 Send/Sync，也不會讓 drop 檢查認為我們擁有一個 Tag。
+    _tag: PhantomData<fn() -> Tag>,
+}
+
+impl<Tag, S: SymbolRepr> TypedSymbol<Tag, S> {
+    fn new(symbol: S) -> Self {
+        TypedSymbol {
+            symbol,
+            _tag: PhantomData,
+        }
+    }
+
+    /// 去掉標籤，返回底層的 Symbol。
+    pub fn untyped(self) -> S {
+        self.symbol
+    }
+}
+
+// 下面這些 trait 都手寫實現，因為 derive 會要求 `Tag` 也實現它們。
+
+impl<Tag, S: SymbolRepr> Clone for TypedSymbol<Tag, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag, S: SymbolRepr> Copy for TypedSymbol<Tag, S> {}
+
+impl<Tag, S: SymbolRepr> PartialEq for TypedSymbol<Tag, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl<Tag, S: SymbolRepr> Eq for TypedSymbol<Tag, S> {}
+
+impl<Tag, S: SymbolRepr> Hash for TypedSymbol<Tag, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+    }
+}
+
+impl<Tag, S: SymbolRepr> fmt::Debug for TypedSymbol<Tag, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedSymbol")
+            .field(&std::any::type_name::<Tag>())
+            .field(&self.symbol.index())
+            .finish()
+    }
+}
+
+/// 一個只產生 [`TypedSymbol<Tag>`] 的 [`Interner`]。
+///
+/// 每個命名空間使用自己的 `TypedInterner`，它們的 Symbol 在類型上互不相容。
+pub struct TypedInterner<Tag, S = Symbol, const MIN_ALIGN: usize = 1> {
+    inner: Interner<S, MIN_ALIGN>,
+    _tag: PhantomData<fn() -> Tag>,
+}
+
+impl<Tag> TypedInterner<Tag> {
+    /// 創建一個空的 TypedInterner，第一次駐留時才會分配內存。
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Tag, S: SymbolRepr, const MIN_ALIGN: usize> Default for TypedInterner<Tag, S, MIN_ALIGN> {
+    fn default() -> Self {
+        Self::from_untyped(Interner::default())
+    }
+}
+
+impl<Tag, S: SymbolRepr, const MIN_ALIGN: usize> TypedInterner<Tag, S, MIN_ALIGN> {
+    /// 見 [`Interner::with_capacity`]。
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_untyped(Interner::with_capacity(capacity))
+    }
+
+    /// 見 [`Interner::with_capacity_and_shards`]。
+    pub fn with_capacity_and_shards(capacity: usize, shards: usize) -> Self {
+        Self::from_untyped(Interner::with_capacity_and_shards(capacity, shards))
+    }
+
+    /// 把一個空的 [`Interner`] 包裝為這個命名空間的 TypedInterner。
+    ///
+    /// # Panics
+    /// 如果 `interner` 不是空的會 panic：其中已有的 Symbol 沒有標籤，
+    /// 無法保證它們不會被當作別的命名空間的 Symbol 使用。
+    pub fn from_untyped(interner: Interner<S, MIN_ALIGN>) -> Self {
+        assert!(
+            interner.is_empty(),
+            "a TypedInterner must start from an empty Interner"
+        );
+        TypedInterner {
+            inner: interner,
+            _tag: PhantomData,
+        }
+    }
+
+    /// 見 [`Interner::intern`]。
+    pub fn intern(&self, s: &str) -> TypedSymbol<Tag, S> {
+        TypedSymbol::new(self.inner.intern(s))
+    }
+
+    /// 見 [`Interner::try_intern`]。
+    pub fn try_intern(&self, s: &str) -> Result<TypedSymbol<Tag, S>, InternError> {
+        self.inner.try_intern(s).map(TypedSymbol::new)
+    }
+
+    /// 見 [`Interner::intern_static`]。
+    pub fn intern_static(&self, s: &'static str) -> TypedSymbol<Tag, S> {
+        TypedSymbol::new(self.inner.intern_static(s))
+    }
+
+    /// 見 [`Interner::get`]。
+    pub fn get(&self, s: &str) -> Option<TypedSymbol<Tag, S>> {
+        self.inner.get(s).map(TypedSymbol::new)
+    }
+
+    /// 見 [`Interner::contains`]。
+    pub fn contains(&self, s: &str) -> bool {
+        self.inner.contains(s)
+    }
+
+    /// 見 [`Interner::resolve`]。只接受同一個命名空間的 Symbol。
+    pub fn resolve(&self, symbol: TypedSymbol<Tag, S>) -> Option<&str> {
+        self.inner.resolve(symbol.symbol)
+    }
+
+    /// 見 [`Interner::len`]。
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// 見 [`Interner::is_empty`]。
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// 見 [`Interner::iter`]。
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (TypedSymbol<Tag, S>, &str)> + '_ {
+        self.inner
+            .iter()
+            .map(|(symbol, s)| (TypedSymbol::new(symbol), s))
+    }
+
+    /// 見 [`Interner::memory_usage`]。
+    pub fn memory_usage(&self) -> usize {
+        self.inner.memory_usage()
+    }
+
+    /// 訪問底層沒有標籤的 [`Interner`]，比如用來寫出快照。
+    ///
+    /// 只提供共享引用：通過它駐留的字符串仍然屬於這個命名空間。
+    pub fn as_untyped(&self) -> &Interner<S, MIN_ALIGN> {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    enum Ident {}
+    enum FilePath {}
+
+    #[test]
+    fn test_typed_interners_are_independent() {
+        let idents: TypedInterner<Ident> = TypedInterner::new();
+        let paths: TypedInterner<FilePath> = TypedInterner::with_capacity(8);
+
+        let main = idents.intern("main");
+        let main_rs = paths.intern("src/main.rs");
+        assert_eq!(idents.intern("main"), main);
+        assert_eq!(idents.get("main"), Some(main));
+        assert_eq!(paths.get("main"), None);
+
+        assert_eq!(idents.resolve(main), Some("main"));
+        assert_eq!(paths.resolve(main_rs), Some("src/main.rs"));
+
+        // 底层编号相同，但它们属于不同的类型
+        assert_eq!(main.untyped(), main_rs.untyped());
+
+        // 类型化的 Symbol 依然是 4 字节，并且可以当作哈希表的键
+        assert_eq!(std::mem::size_of::<Option<TypedSymbol<Ident>>>(), 4);
+        let set: HashSet<TypedSymbol<Ident>> = [main, idents.intern("x"), main].into();
+        assert_eq!(set.len(), 2);
+
+        let listed: Vec<_> = idents.iter().collect();
+        assert_eq!(listed[0], (main, "main"));
+        assert!(format!("{main:?}").contains("Ident"));
+    }
+
+    #[test]
+    #[should_panic(expected = "empty Interner")]
+    fn test_from_untyped_requires_empty_interner() {
+        let interner: Interner = Interner::new();
+        interner.intern("untagged");
+        let _: TypedInterner<Ident> = TypedInterner::from_untyped(interner);
+    }
+}